@@ -0,0 +1,299 @@
+use soroban_sdk::{Env, Vec};
+
+use crate::types::PriceData;
+
+/// Calculate the median of a list of prices. If there are an even number of prices, the
+/// average of the two middle prices is used. The oldest timestamp of the prices is reported.
+///
+/// Returns None if the list is empty
+pub fn median_price(e: &Env, prices: &Vec<PriceData>) -> Option<PriceData> {
+    if prices.is_empty() {
+        return None;
+    }
+    Some(PriceData {
+        price: median_value(e, &price_values(e, prices)),
+        timestamp: oldest_timestamp(prices),
+    })
+}
+
+/// Find the prices that are within `max_mads` median absolute deviations (MAD) of the
+/// median price. If the MAD is zero, only prices equal to the median are kept.
+///
+/// Returns the indexes of the prices that are kept, in their original order
+pub fn filter_outliers(e: &Env, prices: &Vec<PriceData>, max_mads: u32) -> Vec<u32> {
+    let mut kept: Vec<u32> = Vec::new(e);
+    if prices.is_empty() {
+        return kept;
+    }
+    let values = price_values(e, prices);
+    let median = median_value(e, &values);
+    let mut deviations: Vec<i128> = Vec::new(e);
+    for value in values.iter() {
+        deviations.push_back((value - median).abs());
+    }
+    let mad = median_value(e, &deviations);
+    let max_deviation = mad.saturating_mul(max_mads as i128);
+    for (index, deviation) in deviations.iter().enumerate() {
+        if deviation <= max_deviation {
+            kept.push_back(index as u32);
+        }
+    }
+    kept
+}
+
+/// Calculate the mean of a list of prices. The oldest timestamp of the prices is reported.
+///
+/// Returns None if the list is empty
+pub fn mean_price(prices: &Vec<PriceData>) -> Option<PriceData> {
+    if prices.is_empty() {
+        return None;
+    }
+    let mut sum: i128 = 0;
+    for price in prices.iter() {
+        sum += price.price;
+    }
+    Some(PriceData {
+        price: sum / prices.len() as i128,
+        timestamp: oldest_timestamp(prices),
+    })
+}
+
+/// Calculate the weighted mean of a list of prices, where `weights` holds the weight of the
+/// price at the same index. The oldest timestamp of the prices is reported.
+///
+/// Returns None if the list is empty or the weights sum to zero
+pub fn weighted_mean_price(prices: &Vec<PriceData>, weights: &Vec<u32>) -> Option<PriceData> {
+    let mut weighted_sum: i128 = 0;
+    let mut total_weight: i128 = 0;
+    for (price, weight) in prices.iter().zip(weights.iter()) {
+        weighted_sum += price.price * weight as i128;
+        total_weight += weight as i128;
+    }
+    if total_weight == 0 {
+        return None;
+    }
+    Some(PriceData {
+        price: weighted_sum / total_weight,
+        timestamp: oldest_timestamp(prices),
+    })
+}
+
+/// Collect the price values of a list of prices
+fn price_values(e: &Env, prices: &Vec<PriceData>) -> Vec<i128> {
+    let mut values: Vec<i128> = Vec::new(e);
+    for price in prices.iter() {
+        values.push_back(price.price);
+    }
+    values
+}
+
+/// Calculate the median of a non-empty list of values. If there are an even number of values,
+/// the average of the two middle values is used.
+fn median_value(e: &Env, values: &Vec<i128>) -> i128 {
+    let sorted = sort_values(e, values);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        let lower = sorted.get_unchecked(mid - 1);
+        let upper = sorted.get_unchecked(mid);
+        lower + (upper - lower) / 2
+    } else {
+        sorted.get_unchecked(mid)
+    }
+}
+
+/// Sort a list of values in ascending order
+fn sort_values(e: &Env, values: &Vec<i128>) -> Vec<i128> {
+    let mut sorted: Vec<i128> = Vec::new(e);
+    for value in values.iter() {
+        let mut index = 0;
+        while index < sorted.len() && sorted.get_unchecked(index) < value {
+            index += 1;
+        }
+        sorted.insert(index, value);
+    }
+    sorted
+}
+
+/// Find the oldest timestamp of a non-empty list of prices
+fn oldest_timestamp(prices: &Vec<PriceData>) -> u64 {
+    let mut timestamp = u64::MAX;
+    for price in prices.iter() {
+        timestamp = timestamp.min(price.timestamp);
+    }
+    timestamp
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::vec;
+
+    #[test]
+    fn test_median_price() {
+        let e = Env::default();
+
+        let prices = vec![
+            &e,
+            PriceData {
+                price: 1_0500000,
+                timestamp: 300,
+            },
+            PriceData {
+                price: 0_9500000,
+                timestamp: 600,
+            },
+            PriceData {
+                price: 1_0000000,
+                timestamp: 600,
+            },
+        ];
+        let median = median_price(&e, &prices).unwrap();
+        assert_eq!(median.price, 1_0000000);
+        assert_eq!(median.timestamp, 300);
+
+        let mut prices_even = prices.clone();
+        prices_even.push_back(PriceData {
+            price: 2_0000000,
+            timestamp: 900,
+        });
+        let median = median_price(&e, &prices_even).unwrap();
+        assert_eq!(median.price, 1_0250000);
+        assert_eq!(median.timestamp, 300);
+
+        let single = vec![
+            &e,
+            PriceData {
+                price: 1_2345678,
+                timestamp: 900,
+            },
+        ];
+        let median = median_price(&e, &single).unwrap();
+        assert_eq!(median.price, 1_2345678);
+        assert_eq!(median.timestamp, 900);
+
+        assert!(median_price(&e, &Vec::new(&e)).is_none());
+    }
+
+    #[test]
+    fn test_mean_price() {
+        let e = Env::default();
+
+        let prices = vec![
+            &e,
+            PriceData {
+                price: 1_0000000,
+                timestamp: 600,
+            },
+            PriceData {
+                price: 1_1000000,
+                timestamp: 300,
+            },
+            PriceData {
+                price: 0_9500000,
+                timestamp: 600,
+            },
+        ];
+        let mean = mean_price(&prices).unwrap();
+        assert_eq!(mean.price, 1_0166666);
+        assert_eq!(mean.timestamp, 300);
+
+        assert!(mean_price(&Vec::new(&e)).is_none());
+    }
+
+    #[test]
+    fn test_weighted_mean_price() {
+        let e = Env::default();
+
+        let prices = vec![
+            &e,
+            PriceData {
+                price: 1_0000000,
+                timestamp: 600,
+            },
+            PriceData {
+                price: 1_1000000,
+                timestamp: 300,
+            },
+            PriceData {
+                price: 0_9000000,
+                timestamp: 600,
+            },
+        ];
+        let mean = weighted_mean_price(&prices, &vec![&e, 2, 1, 1]).unwrap();
+        assert_eq!(mean.price, 1_0000000);
+        assert_eq!(mean.timestamp, 300);
+
+        let mean = weighted_mean_price(&prices, &vec![&e, 1, 3, 0]).unwrap();
+        assert_eq!(mean.price, 1_0750000);
+        assert_eq!(mean.timestamp, 300);
+
+        // rounds down
+        let mean = weighted_mean_price(&prices, &vec![&e, 1, 1, 1]).unwrap();
+        assert_eq!(mean.price, 1_0000000);
+        let mean = weighted_mean_price(&prices, &vec![&e, 0, 2, 1]).unwrap();
+        assert_eq!(mean.price, 1_0333333);
+
+        assert!(weighted_mean_price(&prices, &vec![&e, 0, 0, 0]).is_none());
+        assert!(weighted_mean_price(&Vec::new(&e), &Vec::new(&e)).is_none());
+    }
+
+    #[test]
+    fn test_filter_outliers() {
+        let e = Env::default();
+
+        let prices = vec![
+            &e,
+            PriceData {
+                price: 1_0000000,
+                timestamp: 600,
+            },
+            PriceData {
+                price: 1_0100000,
+                timestamp: 600,
+            },
+            PriceData {
+                price: 1_5000000,
+                timestamp: 600,
+            },
+            PriceData {
+                price: 0_9900000,
+                timestamp: 600,
+            },
+            PriceData {
+                price: 1_0300000,
+                timestamp: 600,
+            },
+        ];
+        // median = 1.01, deviations = [0.01, 0, 0.49, 0.02, 0.02], MAD = 0.02
+        let kept = filter_outliers(&e, &prices, 3);
+        assert_eq!(kept, vec![&e, 0, 1, 3, 4]);
+
+        // a deviation equal to the limit is kept
+        let kept = filter_outliers(&e, &prices, 1);
+        assert_eq!(kept, vec![&e, 0, 1, 3, 4]);
+
+        let kept = filter_outliers(&e, &prices, 0);
+        assert_eq!(kept, vec![&e, 1]);
+
+        // MAD of zero only keeps prices equal to the median
+        let prices_equal = vec![
+            &e,
+            PriceData {
+                price: 1_0000000,
+                timestamp: 600,
+            },
+            PriceData {
+                price: 1_0000000,
+                timestamp: 600,
+            },
+            PriceData {
+                price: 1_0000001,
+                timestamp: 600,
+            },
+        ];
+        let kept = filter_outliers(&e, &prices_equal, 10);
+        assert_eq!(kept, vec![&e, 0, 1]);
+
+        assert_eq!(filter_outliers(&e, &Vec::new(&e), 3).len(), 0);
+    }
+}
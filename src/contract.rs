@@ -1,10 +1,18 @@
 use crate::{
     errors::OracleAggregatorErrors,
-    price_data::get_price,
+    events::OracleAggregatorEvents,
+    price_data::{
+        cross_price, cross_prices, fixed_div_floor, get_aggregate_price, get_fallback_price,
+        get_price, get_price_at, get_price_confidence, get_prices, get_twap, is_valid_roc_window,
+    },
     storage,
-    types::{Asset, AssetConfig, OracleConfig, PriceData, PriceFeedClient},
+    types::{
+        AggregatePriceData, Aggregation, Asset, AssetConfig, BreakerState, DeviationMode,
+        OracleConfig, PriceBounds, PriceConfidence, PriceData, PriceFeedClient, Reference,
+        ReferenceConfig, SourceConfig,
+    },
 };
-use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Map, Vec};
+use soroban_sdk::{contract, contractimpl, panic_with_error, vec, Address, Env, Map, Vec};
 
 #[contract]
 pub struct OracleAggregator;
@@ -30,6 +38,7 @@ impl OracleAggregator {
             panic_with_error!(&e, OracleAggregatorErrors::InvalidMaxAge);
         }
         storage::set_max_age(&e, &max_age);
+        storage::set_version(&e, &storage::STORAGE_VERSION);
     }
 
     /**** Read Only *****/
@@ -59,6 +68,42 @@ impl OracleAggregator {
         storage::get_decimals(&e)
     }
 
+    // Fetch the guardian address. The guardian defaults to the admin if no guardian is set.
+    pub fn guardian(e: Env) -> Address {
+        storage::get_guardian(&e)
+    }
+
+    // Fetch the resolution of the oracle aggregator, in seconds. This is the coarsest
+    // resolution of the supported oracles, or 0 if no oracles are supported.
+    pub fn resolution(e: Env) -> u32 {
+        let oracles = storage::get_oracles(&e);
+        let mut resolution: u32 = 0;
+        for oracle in oracles.iter() {
+            resolution = resolution.max(oracle.resolution);
+        }
+        resolution
+    }
+
+    // Fetch the last timestamp the oracle aggregator was updated. This is the oldest last
+    // timestamp of the supported oracles that is within the max age, or 0 if no oracles
+    // are within the max age.
+    pub fn last_timestamp(e: Env) -> u64 {
+        let oracles = storage::get_oracles(&e);
+        let max_age = storage::get_max_age(&e);
+        let oldest_timestamp = e.ledger().timestamp().saturating_sub(max_age);
+        let mut last_timestamp: u64 = 0;
+        for oracle in oracles.iter() {
+            let oracle_client = PriceFeedClient::new(&e, &oracle.address);
+            let round_timestamp = oracle_client.last_timestamp();
+            if round_timestamp >= oldest_timestamp
+                && (last_timestamp == 0 || round_timestamp < last_timestamp)
+            {
+                last_timestamp = round_timestamp;
+            }
+        }
+        last_timestamp
+    }
+
     // Fetch the list of assets the oracle supports
     pub fn assets(e: Env) -> Vec<Asset> {
         let asset_configs = storage::get_asset_configs(&e);
@@ -67,42 +112,388 @@ impl OracleAggregator {
         base_assets
     }
 
-    // Fetch the last price of the Asset based on the asset config.
+    // Fetch the last price of the Asset based on the asset config. If the asset has the circuit
+    // breaker enabled and the price fails a deviation check or its sources disagree, the breaker
+    // is tripped and its state is written to storage.
     //
     // ### Arguments
     // * `asset` - The asset to fetch the price for
     //
     // ### Returns
     // * The lastprice from the source oracle
-    // * None if the price cannot be resolved, or is outside the configured bounds
+    // * The last accepted price, if the sources cannot report a price and the asset has a grace
+    //   period that has not passed
+    // * None if the price cannot be resolved, is outside the configured bounds, or the circuit
+    //   breaker of the asset is tripped
     //
     // ### Errors
     // * `AssetNotFound` - The asset is not in the list of assets
     // * `OracleNotFound` - The oracle is not in the list of oracles
     pub fn lastprice(e: Env, asset: Asset) -> Option<PriceData> {
         storage::extend_instance(&e);
+        load_lastprice(&e, asset)
+    }
 
-        let base_asset = storage::get_base(&e);
-        let base_assets = storage::get_base_assets(&e);
-        if base_assets.contains(&asset) || asset == base_asset {
-            let decimals = storage::get_decimals(&e);
-            return Some(PriceData {
-                price: 10i128.pow(decimals),
-                timestamp: e.ledger().timestamp(),
-            });
+    // Fetch the last price of the Asset based on the asset config, along with the sources
+    // used to calculate it. Unlike `self.lastprice`, the reason a price cannot be resolved
+    // is returned as an error.
+    //
+    // ### Arguments
+    // * `asset` - The asset to fetch the price for
+    //
+    // ### Returns
+    // * The lastprice as reported by `self.lastprice`, the indexes of the oracles used, and if
+    //   the price is a fallback to the last accepted price. Base and base-like assets and fallback
+    //   prices do not use any oracles.
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `OracleNotFound` - The oracle is not in the list of oracles
+    // * `NoValidPrice` - No source reported a valid price
+    // * `NonPositivePrice` - Too few sources reported a valid price, and a source reported a
+    //   price of zero or less
+    // * `FutureTimestamp` - Too few sources reported a valid price, and a source reported a
+    //   timestamp too far past the ledger timestamp
+    // * `MisalignedTimestamp` - Too few sources reported a valid price, and a source reported a
+    //   timestamp not aligned to its resolution
+    // * `OracleNotLive` - No source reported a valid price, and a source oracle missed too many
+    //   rounds
+    // * `MissingRound` - Too few sources reported a valid price, and a source skipped a round
+    //   compared in the max_dev check
+    // * `MaxDevExceeded` - Too few sources reported a valid price, and a source failed the
+    //   max_dev check
+    // * `MaxRateOfChangeExceeded` - Too few sources reported a valid price, and a source failed
+    //   the rate of change check
+    // * `QuorumNotMet` - Fewer than `min_sources` sources reported a valid price, and every other
+    //   source was unavailable
+    // * `TooManyOutliers` - Enough sources reported a valid price, but fewer than `min_sources`
+    //   remain after dropping outliers
+    // * `ReferenceUnavailable` - The reference oracle did not report a valid price
+    // * `MaxDivergenceExceeded` - The price diverged too far from the reference price
+    // * `MaxStepExceeded` - The price deviated too far from the last accepted price
+    // * `PriceOutOfBounds` - The price is out of bounds, and the bounds policy is to reject it
+    // * `BreakerTripped` - The circuit breaker of the asset is tripped
+    pub fn aggregate_lastprice(
+        e: Env,
+        asset: Asset,
+    ) -> Result<AggregatePriceData, OracleAggregatorErrors> {
+        storage::extend_instance(&e);
+        load_aggregate_lastprice(&e, asset).map(|(price, _)| price)
+    }
+
+    // Fetch the last price of the Asset based on the asset config, along with a confidence
+    // band of the source prices used to calculate it.
+    //
+    // ### Arguments
+    // * `asset` - The asset to fetch the price for
+    //
+    // ### Returns
+    // * The lastprice as reported by `self.lastprice`, the min and max source price used,
+    //   the spread between them in basis points of the price, and the number of sources used.
+    //   Base and base-like assets report no spread and do not use any oracles.
+    //
+    // ### Errors
+    // * Any error returned by `self.aggregate_lastprice`
+    pub fn confidence(e: Env, asset: Asset) -> Result<PriceConfidence, OracleAggregatorErrors> {
+        storage::extend_instance(&e);
+        let (price, source_prices) = load_aggregate_lastprice(&e, asset)?;
+        Ok(get_price_confidence(&e, &price, &source_prices))
+    }
+
+    // Fetch the last price of the Asset, and store it as the last accepted price of the asset
+    // if it is newer than the current last accepted price. The price also becomes the step
+    // anchor of the asset once the step window of the current anchor has passed. This can be
+    // called by anyone.
+    //
+    // ### Arguments
+    // * `asset` - The asset to fetch the price for
+    //
+    // ### Returns
+    // * The lastprice as reported by `self.lastprice`. Base and base-like assets do not store
+    //   a last accepted price.
+    //
+    // ### Errors
+    // * Any error returned by `self.aggregate_lastprice`
+    pub fn poke(e: Env, asset: Asset) -> Result<PriceData, OracleAggregatorErrors> {
+        storage::extend_instance(&e);
+        let (price, _) = load_aggregate_lastprice(&e, asset.clone())?;
+        let price = PriceData {
+            price: price.price,
+            timestamp: price.timestamp,
+        };
+        if !is_base_asset(&e, &asset) {
+            let is_newer = match storage::get_last_price(&e, &asset) {
+                Some(last_price) => price.timestamp > last_price.timestamp,
+                None => true,
+            };
+            if is_newer {
+                storage::set_last_price(&e, &asset, &price);
+            }
+            // keep the anchor fixed for the step window, so the price cannot walk past
+            // max_step by being poked every round
+            let step_window = storage::get_asset_configs(&e)
+                .get_unchecked(asset.clone())
+                .step_window;
+            let is_expired = match storage::get_step_anchor(&e, &asset) {
+                Some(anchor) => price.timestamp > anchor.timestamp.saturating_add(step_window),
+                None => true,
+            };
+            if is_expired {
+                storage::set_step_anchor(&e, &asset, &price);
+            }
         }
+        Ok(price)
+    }
+
+    // Fetch the last accepted price of the Asset, as stored by `self.poke`
+    //
+    // ### Arguments
+    // * `asset` - The asset to fetch the price for
+    //
+    // ### Returns
+    // * The last accepted price, or None if the asset has never been poked
+    pub fn last_accepted_price(e: Env, asset: Asset) -> Option<PriceData> {
+        storage::extend_instance(&e);
+        storage::get_last_price(&e, &asset)
+    }
+
+    // Fetch the step anchor of the Asset, as stored by `self.poke`
+    //
+    // ### Arguments
+    // * `asset` - The asset to fetch the step anchor for
+    //
+    // ### Returns
+    // * The last accepted price the max_step check is anchored to, or None if the asset has
+    //   never been poked
+    pub fn step_anchor(e: Env, asset: Asset) -> Option<PriceData> {
+        storage::extend_instance(&e);
+        storage::get_step_anchor(&e, &asset)
+    }
+
+    // Fetch the state of the circuit breaker of the Asset
+    //
+    // ### Arguments
+    // * `asset` - The asset to fetch the circuit breaker for
+    //
+    // ### Returns
+    // * The state of the circuit breaker, or None if it is not tripped
+    pub fn breaker(e: Env, asset: Asset) -> Option<BreakerState> {
+        storage::extend_instance(&e);
+        storage::get_breaker(&e, &asset)
+    }
 
+    // Check the last price of the Asset, and trip its circuit breaker if the asset has the
+    // circuit breaker enabled and the price fails a deviation check or its sources disagree.
+    // Once tripped, no price is reported for the asset until the guardian resets the breaker.
+    // This can be called by anyone.
+    //
+    // ### Arguments
+    // * `asset` - The asset to check
+    //
+    // ### Returns
+    // * True if the circuit breaker of the asset is tripped, false otherwise. Base and base-like
+    //   assets do not have a circuit breaker.
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `OracleNotFound` - The oracle is not in the list of oracles
+    pub fn check_breaker(e: Env, asset: Asset) -> bool {
+        storage::extend_instance(&e);
+        if is_base_asset(&e, &asset) {
+            return false;
+        }
         let configs = storage::get_asset_configs(&e);
-        let oracles = storage::get_oracles(&e);
-        if let Some(config) = configs.get(asset) {
-            if let Some(oracle) = oracles.get(config.oracle_index) {
-                crate::price_data::get_price(&e, &oracle, &config)
-            } else {
-                panic_with_error!(&e, OracleAggregatorErrors::OracleNotFound);
-            }
-        } else {
+        let config = configs.get(asset.clone()).unwrap_or_else(|| {
             panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+        if storage::get_breaker(&e, &asset).is_some() {
+            return true;
+        }
+        if !config.circuit_breaker {
+            return false;
+        }
+
+        match get_aggregate_price(&e, &asset, &config) {
+            Err(error) if is_breaker_error(error) => {
+                trip_breaker(&e, asset, error);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Fetch the price of the Asset at a specific timestamp based on the asset config. Only the
+    // primary oracle of the asset is used, and any additional sources are ignored.
+    //
+    // ### Arguments
+    // * `asset` - The asset to fetch the price for
+    // * `timestamp` - The timestamp of the round to fetch the price for
+    //
+    // ### Returns
+    // * The price from the source oracle for the round
+    // * None if the source oracle does not have a price for the round, or the circuit breaker
+    //   of the asset is tripped
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `OracleNotFound` - The oracle is not in the list of oracles
+    pub fn price(e: Env, asset: Asset, timestamp: u64) -> Option<PriceData> {
+        storage::extend_instance(&e);
+        load_price(&e, asset, timestamp)
+    }
+
+    // Fetch the last N price records of the Asset based on the asset config. Rounds older than
+    // `max_age * records` seconds are filtered out. Only the primary oracle of the asset is used,
+    // and any additional sources are ignored.
+    //
+    // ### Arguments
+    // * `asset` - The asset to fetch the prices for
+    // * `records` - The number of rounds to fetch
+    //
+    // ### Returns
+    // * The prices from the source oracle, ordered from most recent to oldest
+    // * None if no recent enough prices could be resolved, or the circuit breaker of the asset
+    //   is tripped
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `OracleNotFound` - The oracle is not in the list of oracles
+    pub fn prices(e: Env, asset: Asset, records: u32) -> Option<Vec<PriceData>> {
+        storage::extend_instance(&e);
+        load_prices(&e, asset, records)
+    }
+
+    // Fetch the last price of the base asset, quoted in the quote asset. Both prices are
+    // resolved as they would be by `self.lastprice`.
+    //
+    // ### Arguments
+    // * `base_asset` - The asset to fetch the price for
+    // * `quote_asset` - The asset the price is quoted in
+    //
+    // ### Returns
+    // * The cross price, with the timestamp of the older of the two prices
+    // * None if either price cannot be resolved, or is outside the configured bounds
+    //
+    // ### Errors
+    // * `AssetNotFound` - Either asset is not in the list of assets
+    // * `OracleNotFound` - The oracle is not in the list of oracles
+    pub fn x_last_price(e: Env, base_asset: Asset, quote_asset: Asset) -> Option<PriceData> {
+        storage::extend_instance(&e);
+        let base_price = load_lastprice(&e, base_asset)?;
+        let quote_price = load_lastprice(&e, quote_asset)?;
+        cross_price(&e, &base_price, &quote_price, storage::get_decimals(&e))
+    }
+
+    // Fetch the price of the base asset, quoted in the quote asset, at a specific timestamp.
+    // Both prices are resolved as they would be by `self.price`.
+    //
+    // ### Arguments
+    // * `base_asset` - The asset to fetch the price for
+    // * `quote_asset` - The asset the price is quoted in
+    // * `timestamp` - The timestamp of the round to fetch the price for
+    //
+    // ### Returns
+    // * The cross price for the round
+    // * None if either source oracle does not have a price for the round
+    //
+    // ### Errors
+    // * `AssetNotFound` - Either asset is not in the list of assets
+    // * `OracleNotFound` - The oracle is not in the list of oracles
+    pub fn x_price(
+        e: Env,
+        base_asset: Asset,
+        quote_asset: Asset,
+        timestamp: u64,
+    ) -> Option<PriceData> {
+        storage::extend_instance(&e);
+        let base_price = load_price(&e, base_asset, timestamp)?;
+        let quote_price = load_price(&e, quote_asset, timestamp)?;
+        cross_price(&e, &base_price, &quote_price, storage::get_decimals(&e))
+    }
+
+    // Fetch the last N price records of the base asset, quoted in the quote asset. Both
+    // price histories are resolved as they would be by `self.prices`, and only rounds
+    // reported for both assets are included.
+    //
+    // ### Arguments
+    // * `base_asset` - The asset to fetch the prices for
+    // * `quote_asset` - The asset the prices are quoted in
+    // * `records` - The number of rounds to fetch
+    //
+    // ### Returns
+    // * The cross prices, ordered from most recent to oldest
+    // * None if no rounds could be resolved for both assets
+    //
+    // ### Errors
+    // * `AssetNotFound` - Either asset is not in the list of assets
+    // * `OracleNotFound` - The oracle is not in the list of oracles
+    pub fn x_prices(
+        e: Env,
+        base_asset: Asset,
+        quote_asset: Asset,
+        records: u32,
+    ) -> Option<Vec<PriceData>> {
+        storage::extend_instance(&e);
+        if is_base_asset(&e, &quote_asset) {
+            // prices quoted in a base asset are the prices of the base asset
+            return load_prices(&e, base_asset, records);
         }
+
+        let quote_prices = load_prices(&e, quote_asset, records)?;
+        let base_prices = if is_base_asset(&e, &base_asset) {
+            // report the base asset price for each round of the quote asset
+            let mut base_prices: Vec<PriceData> = Vec::new(&e);
+            for quote_price in quote_prices.iter() {
+                base_prices.push_back(base_price(&e, quote_price.timestamp));
+            }
+            base_prices
+        } else {
+            load_prices(&e, base_asset, records)?
+        };
+        cross_prices(&e, &base_prices, &quote_prices, storage::get_decimals(&e))
+    }
+
+    // Fetch the time-weighted average price of the Asset over the last N rounds based on the
+    // asset config. Only the primary oracle of the asset is used, and any additional sources are
+    // ignored.
+    //
+    // ### Arguments
+    // * `asset` - The asset to fetch the price for
+    // * `records` - The number of rounds to average
+    //
+    // ### Returns
+    // * The average price over the rounds
+    // * None if the source oracle did not report `records` contiguous rounds, or any round
+    //   is older than `max_age * records` seconds, or the circuit breaker of the asset is tripped
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `OracleNotFound` - The oracle is not in the list of oracles
+    pub fn twap(e: Env, asset: Asset, records: u32) -> Option<i128> {
+        storage::extend_instance(&e);
+        load_twap(&e, asset, records)
+    }
+
+    // Fetch the time-weighted average price of the base asset, quoted in the quote asset, over
+    // the last N rounds. Both prices are resolved as they would be by `self.twap`.
+    //
+    // ### Arguments
+    // * `base_asset` - The asset to fetch the price for
+    // * `quote_asset` - The asset the price is quoted in
+    // * `records` - The number of rounds to average
+    //
+    // ### Returns
+    // * The average cross price over the rounds
+    // * None if either average price cannot be resolved
+    //
+    // ### Errors
+    // * `AssetNotFound` - Either asset is not in the list of assets
+    // * `OracleNotFound` - The oracle is not in the list of oracles
+    pub fn x_twap(e: Env, base_asset: Asset, quote_asset: Asset, records: u32) -> Option<i128> {
+        storage::extend_instance(&e);
+        let base_price = load_twap(&e, base_asset, records)?;
+        let quote_price = load_twap(&e, quote_asset, records)?;
+        fixed_div_floor(&e, base_price, quote_price, storage::get_decimals(&e))
     }
 
     /***** Admin Functions *****/
@@ -116,6 +507,76 @@ impl OracleAggregator {
         storage::set_admin(&e, &admin);
     }
 
+    // (Admin Only) Migrate storage written by an earlier version of the oracle aggregator to the
+    // current storage layout. Oracle and asset configs stored in the original layout are
+    // re-encoded, keeping every original setting, with every newer setting at the default used
+    // by `add_oracle` and `add_asset`.
+    //
+    // ### Errors
+    // * `AlreadyMigrated` - The storage is already in the current layout
+    pub fn migrate(e: Env) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+        if storage::get_version(&e) >= storage::STORAGE_VERSION {
+            panic_with_error!(&e, OracleAggregatorErrors::AlreadyMigrated);
+        }
+
+        let mut oracles: Vec<OracleConfig> = Vec::new(&e);
+        for legacy_oracle in storage::get_legacy_oracles(&e).iter() {
+            oracles.push_back(OracleConfig {
+                address: legacy_oracle.address,
+                index: legacy_oracle.index,
+                resolution: legacy_oracle.resolution,
+                decimals: legacy_oracle.decimals,
+                liveness_rounds: 0,
+            });
+        }
+        storage::set_oracles(&e, &oracles);
+
+        let mut configs: Map<Asset, AssetConfig> = Map::new(&e);
+        for (asset, legacy_config) in storage::get_legacy_asset_configs(&e).iter() {
+            let config = new_asset_config(
+                &e,
+                legacy_config.asset,
+                legacy_config.oracle_index,
+                legacy_config.max_dev,
+            );
+            configs.set(asset, config);
+        }
+        storage::set_asset_configs(&e, &configs);
+        storage::set_version(&e, &storage::STORAGE_VERSION);
+    }
+
+    // (Admin Only) Set the guardian address. The guardian can reset the circuit breaker of
+    // an asset.
+    //
+    // ### Arguments
+    // * `guardian` - The address of the new guardian
+    pub fn set_guardian(e: Env, guardian: Address) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+        storage::set_guardian(&e, &guardian);
+    }
+
+    // (Guardian Only) Reset the tripped circuit breaker of an asset
+    //
+    // ### Arguments
+    // * `asset` - The asset to reset the circuit breaker for
+    //
+    // ### Errors
+    // * `BreakerNotTripped` - The circuit breaker of the asset is not tripped
+    pub fn reset_breaker(e: Env, asset: Asset) {
+        let guardian = storage::get_guardian(&e);
+        guardian.require_auth();
+        storage::extend_instance(&e);
+
+        if storage::get_breaker(&e, &asset).is_none() {
+            panic_with_error!(&e, OracleAggregatorErrors::BreakerNotTripped);
+        }
+        storage::remove_breaker(&e, &asset);
+        OracleAggregatorEvents::breaker_reset(&e, asset, guardian);
+    }
+
     // (Admin Only) Add an oracle to the oracle aggregator
     //
     // ### Arguments
@@ -142,11 +603,33 @@ impl OracleAggregator {
             index: oracles.len() as u32,
             resolution: oracle_client.resolution(),
             decimals: oracle_client.decimals(),
+            liveness_rounds: 0,
         };
         oracles.push_back(oracle_config);
         storage::set_oracles(&e, &oracles);
     }
 
+    // (Admin Only) Set the liveness rule of an oracle. The latest round of the oracle used for a
+    // price must be within `liveness_rounds` times the oracle's resolution of the ledger timestamp,
+    // in addition to the max age.
+    //
+    // ### Arguments
+    // * `oracle_id` - The address of the oracle
+    // * `liveness_rounds` - The number of rounds, or 0 to not check the liveness of the oracle
+    //
+    // ### Errors
+    // * `OracleNotFound` - The oracle is not in the list of oracles
+    pub fn set_oracle_liveness(e: Env, oracle_id: Address, liveness_rounds: u32) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut oracle_config = find_oracle(&e, &oracle_id);
+        oracle_config.liveness_rounds = liveness_rounds;
+        let mut oracles = storage::get_oracles(&e);
+        oracles.set(oracle_config.index, oracle_config);
+        storage::set_oracles(&e, &oracles);
+    }
+
     // (Admin Only) Add an asset to the oracle aggregator
     //
     // ### Arguments
@@ -186,24 +669,9 @@ impl OracleAggregator {
             panic_with_error!(&e, OracleAggregatorErrors::AssetExists);
         }
 
-        let oracles = storage::get_oracles(&e);
-        let mut oracle_config: Option<OracleConfig> = None;
-        for oracle in oracles.iter() {
-            if oracle.address == oracle_id {
-                oracle_config = Some(oracle);
-                break;
-            }
-        }
-        let oracle_config = oracle_config.unwrap_or_else(|| {
-            panic_with_error!(&e, OracleAggregatorErrors::OracleNotFound);
-        });
-
-        let config = AssetConfig {
-            asset: oracle_asset,
-            oracle_index: oracle_config.index,
-            max_dev,
-        };
-        let price = get_price(&e, &oracle_config, &config);
+        let oracle_config = find_oracle(&e, &oracle_id);
+        let config = new_asset_config(&e, oracle_asset, oracle_config.index, max_dev);
+        let price = get_price(&e, &oracle_config, &config.asset, &config).ok();
         if let Some(price) = price {
             // able to fetch a price for the asset, add asset and return price
             // if asset is currently on the base assets list, remove it
@@ -220,34 +688,809 @@ impl OracleAggregator {
         }
     }
 
-    // (Admin Only) Add an asset that reports a price of 1 base asset. This should be used
-    // sparingly and only for assets that are redeemable for the base asset, and no safe oracle exists.
+    // (Admin Only) Add an additional source oracle to an asset. Once an asset has additional
+    // sources, its last price is aggregated from the fresh prices reported by each source.
     //
     // ### Arguments
-    // * `base` - The asset to add
-    pub fn add_base_asset(e: Env, base: Asset) {
+    // * `asset` - The asset to add the source to
+    // * `oracle_id` - The address of the source oracle
+    // * `oracle_asset` - The asset used to fetch the source oracle price
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `OracleNotFound` - The oracle is not in the list of oracles
+    // * `SourceExists` - The oracle is already a source for the asset
+    // * `MaxSourcesExceeded` - The asset already has the maximum number of sources
+    // * `InvalidWindow` - The rate of change window of the asset needs more than 30 rounds of
+    //   the source oracle
+    // * `InvalidAssetOracle` - Unable to fetch a price for the oracle asset
+    //
+    // ### Returns
+    // The price data of the asset as reported by the new source. This is useful
+    // for simulation to verify the source was added correctly.
+    pub fn add_asset_source(
+        e: Env,
+        asset: Asset,
+        oracle_id: Address,
+        oracle_asset: Asset,
+    ) -> PriceData {
         storage::get_admin(&e).require_auth();
         storage::extend_instance(&e);
 
-        let base_asset = storage::get_base(&e);
-        if base == base_asset {
-            panic_with_error!(&e, OracleAggregatorErrors::AssetExists);
-        }
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
 
-        let mut base_assets = storage::get_base_assets(&e);
-        if base_assets.contains(&base) {
-            panic_with_error!(&e, OracleAggregatorErrors::AssetExists);
+        // verify the oracle is not already used by the asset and the source list is not full
+        let oracle_config = find_oracle(&e, &oracle_id);
+        if is_oracle_used(&config, oracle_config.index) {
+            panic_with_error!(&e, OracleAggregatorErrors::SourceExists);
         }
-        if base_assets.len() >= 10 {
-            panic_with_error!(&e, OracleAggregatorErrors::MaxAssetsExceeded);
+        if config.sources.len() >= 9 {
+            panic_with_error!(&e, OracleAggregatorErrors::MaxSourcesExceeded);
         }
-
-        let configs = storage::get_asset_configs(&e);
-        if configs.contains_key(base.clone()) {
-            panic_with_error!(&e, OracleAggregatorErrors::AssetExists);
+        if !is_valid_roc_window(&oracle_config, &config) {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidWindow);
         }
 
-        base_assets.push_back(base);
+        let price = get_price(&e, &oracle_config, &oracle_asset, &config).ok();
+        if let Some(price) = price {
+            config.sources.push_back(SourceConfig {
+                asset: oracle_asset,
+                oracle_index: oracle_config.index,
+                weight: 1,
+            });
+            configs.set(asset, config);
+            storage::set_asset_configs(&e, &configs);
+            price
+        } else {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidAssetOracle);
+        }
+    }
+
+    // (Admin Only) Remove an additional source oracle from an asset. If the asset is left with
+    // fewer sources than `min_sources`, `min_sources` is lowered to the number of sources left.
+    //
+    // ### Arguments
+    // * `asset` - The asset to remove the source from
+    // * `oracle_id` - The address of the source oracle
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `OracleNotFound` - The oracle is not in the list of oracles
+    // * `SourceNotFound` - The oracle is not an additional source for the asset
+    pub fn remove_asset_source(e: Env, asset: Asset, oracle_id: Address) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+
+        let oracle_config = find_oracle(&e, &oracle_id);
+        let source_index = config
+            .sources
+            .iter()
+            .position(|source| source.oracle_index == oracle_config.index)
+            .unwrap_or_else(|| {
+                panic_with_error!(&e, OracleAggregatorErrors::SourceNotFound);
+            });
+        config.sources.remove(source_index as u32);
+        if config.min_sources > config.sources.len() + 1 {
+            config.min_sources = config.sources.len() + 1;
+        }
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Set the method used to aggregate the prices of an asset's sources
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `aggregation` - The aggregation method
+    // * `weights` - The weight of each source, starting with the primary oracle followed by
+    //               each additional source in the order they were added
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `InvalidWeights` - A weight is not provided for each source, or all weights are zero
+    pub fn set_aggregation(e: Env, asset: Asset, aggregation: Aggregation, weights: Vec<u32>) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+        if weights.len() != config.sources.len() + 1 || weights.iter().all(|weight| weight == 0) {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidWeights);
+        }
+
+        config.weight = weights.get_unchecked(0);
+        let mut sources = vec![&e];
+        for (mut source, weight) in config.sources.iter().zip(weights.iter().skip(1)) {
+            source.weight = weight;
+            sources.push_back(source);
+        }
+        config.sources = sources;
+        config.aggregation = aggregation;
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Set the reference oracle used to verify the price of an asset. If the price
+    // of the asset diverges from the reference price by `max_divergence` or more, or the reference
+    // oracle cannot report a price, no price is reported for the asset.
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `oracle_id` - The address of the reference oracle
+    // * `oracle_asset` - The asset used to fetch the reference oracle price
+    // * `max_divergence` - The maximum divergence allowed from the reference price, as a percentage
+    //                      with 0 decimals
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `OracleNotFound` - The oracle is not in the list of oracles
+    // * `SourceExists` - The oracle is already a source for the asset
+    // * `InvalidMaxDivergence` - The max divergence is not between 1 and 99
+    // * `InvalidWindow` - The rate of change window of the asset needs more than 30 rounds of
+    //   the reference oracle
+    // * `InvalidAssetOracle` - Unable to fetch a price for the oracle asset
+    //
+    // ### Returns
+    // The price data of the asset as reported by the reference oracle. This is useful
+    // for simulation to verify the reference was set correctly.
+    pub fn set_reference(
+        e: Env,
+        asset: Asset,
+        oracle_id: Address,
+        oracle_asset: Asset,
+        max_divergence: u32,
+    ) -> PriceData {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+
+        // verify the reference oracle is independent of the asset's sources
+        let oracle_config = find_oracle(&e, &oracle_id);
+        if config.oracle_index == oracle_config.index
+            || config
+                .sources
+                .iter()
+                .any(|source| source.oracle_index == oracle_config.index)
+        {
+            panic_with_error!(&e, OracleAggregatorErrors::SourceExists);
+        }
+        if max_divergence == 0 || max_divergence >= 100 {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidMaxDivergence);
+        }
+        if !is_valid_roc_window(&oracle_config, &config) {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidWindow);
+        }
+
+        let price = get_price(&e, &oracle_config, &oracle_asset, &config).ok();
+        if let Some(price) = price {
+            config.reference = Reference::Oracle(ReferenceConfig {
+                asset: oracle_asset,
+                oracle_index: oracle_config.index,
+                max_divergence,
+            });
+            configs.set(asset, config);
+            storage::set_asset_configs(&e, &configs);
+            price
+        } else {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidAssetOracle);
+        }
+    }
+
+    // (Admin Only) Set the minimum number of sources that must report a valid price before
+    // a price is reported for an asset
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `min_sources` - The minimum number of sources
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `InvalidMinSources` - The min sources is 0 or greater than the number of sources
+    pub fn set_min_sources(e: Env, asset: Asset, min_sources: u32) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+        if min_sources == 0 || min_sources > config.sources.len() + 1 {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidMinSources);
+        }
+
+        config.min_sources = min_sources;
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Set the maximum deviation allowed for a stable price of an asset, in basis
+    // points. This takes precedence over the `max_dev` percentage the asset was added with.
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `max_dev_bps` - The maximum deviation, in basis points (e.g. 30 => 0.3%). If this is 0,
+    //                   the `max_dev` percentage is used instead.
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `InvalidMaxDev` - The max deviation is 10000 (100%) or more
+    pub fn set_max_dev_bps(e: Env, asset: Asset, max_dev_bps: u32) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+        if max_dev_bps >= 10000 {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidMaxDev);
+        }
+
+        config.max_dev_bps = max_dev_bps;
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Set separate maximum upward and downward deviations allowed for a stable
+    // price of an asset, in basis points. A price moving up from the compared price is checked
+    // against the upward limit, and a price moving down against the downward limit.
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `max_dev_up_bps` - The maximum upward deviation, in basis points. If this is 0, the
+    //                      symmetric max deviation is used instead.
+    // * `max_dev_down_bps` - The maximum downward deviation, in basis points. If this is 0, the
+    //                        symmetric max deviation is used instead.
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `InvalidMaxDev` - A max deviation is 10000 (100%) or more
+    pub fn set_max_dev_limits(e: Env, asset: Asset, max_dev_up_bps: u32, max_dev_down_bps: u32) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+        if max_dev_up_bps >= 10000 || max_dev_down_bps >= 10000 {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidMaxDev);
+        }
+
+        config.max_dev_up_bps = max_dev_up_bps;
+        config.max_dev_down_bps = max_dev_down_bps;
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Set how the max_dev check is done for an asset. The latest round is compared
+    // against the price of the prior rounds fetched, based on the deviation mode.
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `dev_rounds` - The number of rounds to fetch from the source oracle, between 2 and 10
+    // * `dev_mode` - The price the latest round is compared against
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `InvalidDevRounds` - The number of rounds is less than 2 or greater than 10
+    pub fn set_deviation_check(e: Env, asset: Asset, dev_rounds: u32, dev_mode: DeviationMode) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+        if !(2..=10).contains(&dev_rounds) {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidDevRounds);
+        }
+
+        config.dev_rounds = dev_rounds;
+        config.dev_mode = dev_mode;
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Set the tolerance for missing rounds in the max_dev check for an asset. If
+    // the rounds compared are more than the source oracle's resolution plus the tolerance apart,
+    // the source cannot report a price.
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `round_tolerance` - The tolerance in seconds, or None to not check the gap between rounds
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    pub fn set_round_tolerance(e: Env, asset: Asset, round_tolerance: Option<u32>) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+
+        config.round_tolerance = round_tolerance;
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Set the maximum deviation allowed from the last accepted price of an asset.
+    // New prices within `step_window` seconds of the last accepted price must deviate from it
+    // by less than `max_step_bps`.
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `max_step_bps` - The maximum deviation, in basis points, or 0 to disable the check
+    // * `step_window` - The window, in seconds, after the last accepted price
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `InvalidMaxDev` - The max deviation is 10000 (100%) or more
+    // * `InvalidWindow` - The step window is 0 while the check is enabled
+    pub fn set_max_step(e: Env, asset: Asset, max_step_bps: u32, step_window: u64) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+        if max_step_bps >= 10000 {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidMaxDev);
+        }
+        if max_step_bps > 0 && step_window == 0 {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidWindow);
+        }
+
+        config.max_step_bps = max_step_bps;
+        config.step_window = step_window;
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Set the maximum rate of change allowed for a stable price of an asset. The
+    // latest round of each source must have moved less than `max_roc_bps` from every source
+    // round within the last `roc_window` seconds.
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `max_roc_bps` - The maximum change, in basis points, or 0 to disable the check
+    // * `roc_window` - The window, in seconds, the rate of change is checked over
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `InvalidMaxDev` - The max change is 10000 (100%) or more
+    // * `InvalidWindow` - The window is 0 while the check is enabled, or needs more than 30 rounds
+    //   of an oracle used by the asset
+    pub fn set_rate_limit(e: Env, asset: Asset, max_roc_bps: u32, roc_window: u64) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+        if max_roc_bps >= 10000 {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidMaxDev);
+        }
+        if max_roc_bps > 0 && roc_window == 0 {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidWindow);
+        }
+
+        config.max_roc_bps = max_roc_bps;
+        config.roc_window = roc_window;
+        for oracle in storage::get_oracles(&e).iter() {
+            if is_oracle_used(&config, oracle.index) && !is_valid_roc_window(&oracle, &config) {
+                panic_with_error!(&e, OracleAggregatorErrors::InvalidWindow);
+            }
+        }
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Set the hard floor and ceiling of the price of an asset. If the aggregated
+    // price is out of bounds, it is either rejected or clamped to the nearest bound, based on
+    // the bounds policy.
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `bounds` - The price bounds, in the decimals of the oracle aggregator, or None to remove them
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `InvalidPriceBounds` - The min price is negative, or is not less than the max price
+    pub fn set_price_bounds(e: Env, asset: Asset, bounds: PriceBounds) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+        if let PriceBounds::Bounded(bounds_config) = &bounds {
+            if bounds_config.min_price < 0 || bounds_config.min_price >= bounds_config.max_price {
+                panic_with_error!(&e, OracleAggregatorErrors::InvalidPriceBounds);
+            }
+        }
+
+        config.bounds = bounds;
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Set the max age of a price of an asset, in place of the max age of the
+    // oracle aggregator.
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `max_age` - The max age, in seconds, or None to use the max age of the oracle aggregator
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `InvalidMaxAge` - The max age is not between 60 (1m) and 86400 (24h)
+    pub fn set_asset_max_age(e: Env, asset: Asset, max_age: Option<u64>) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+        if let Some(max_age) = max_age {
+            if !(60..=86400).contains(&max_age) {
+                panic_with_error!(&e, OracleAggregatorErrors::InvalidMaxAge);
+            }
+        }
+
+        config.max_age = max_age;
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Set the outlier filter for an asset. Source prices more than `max_mads`
+    // median absolute deviations from the median source price are dropped before aggregating.
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `max_mads` - The maximum number of median absolute deviations, or 0 to disable the filter
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    pub fn set_outlier_filter(e: Env, asset: Asset, max_mads: u32) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+
+        config.max_mads = max_mads;
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Enable or disable the circuit breaker for an asset. When enabled, the
+    // circuit breaker is tripped by `self.check_breaker` if the price fails a deviation or quorum
+    // check. Disabling the circuit breaker does not reset it if it is tripped.
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `enabled` - If the circuit breaker can be tripped
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    pub fn set_circuit_breaker(e: Env, asset: Asset, enabled: bool) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+
+        config.circuit_breaker = enabled;
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Set the grace period of an asset. If the sources of the asset cannot report a
+    // price, the last accepted price of the asset is reported as a fallback for up to
+    // `grace_period` seconds past the max age of the asset.
+    //
+    // ### Arguments
+    // * `asset` - The asset to update
+    // * `grace_period` - The grace period, in seconds, or 0 to not report a fallback price
+    //
+    // ### Errors
+    // * `AssetNotFound` - The asset is not in the list of assets
+    // * `InvalidWindow` - The grace period is more than 86400 (24h)
+    pub fn set_grace_period(e: Env, asset: Asset, grace_period: u64) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let mut configs = storage::get_asset_configs(&e);
+        let mut config = configs.get(asset.clone()).unwrap_or_else(|| {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetNotFound);
+        });
+        if grace_period > 86400 {
+            panic_with_error!(&e, OracleAggregatorErrors::InvalidWindow);
+        }
+
+        config.grace_period = grace_period;
+        configs.set(asset, config);
+        storage::set_asset_configs(&e, &configs);
+    }
+
+    // (Admin Only) Add an asset that reports a price of 1 base asset. This should be used
+    // sparingly and only for assets that are redeemable for the base asset, and no safe oracle exists.
+    //
+    // ### Arguments
+    // * `base` - The asset to add
+    pub fn add_base_asset(e: Env, base: Asset) {
+        storage::get_admin(&e).require_auth();
+        storage::extend_instance(&e);
+
+        let base_asset = storage::get_base(&e);
+        if base == base_asset {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetExists);
+        }
+
+        let mut base_assets = storage::get_base_assets(&e);
+        if base_assets.contains(&base) {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetExists);
+        }
+        if base_assets.len() >= 10 {
+            panic_with_error!(&e, OracleAggregatorErrors::MaxAssetsExceeded);
+        }
+
+        let configs = storage::get_asset_configs(&e);
+        if configs.contains_key(base.clone()) {
+            panic_with_error!(&e, OracleAggregatorErrors::AssetExists);
+        }
+
+        base_assets.push_back(base);
         storage::set_base_assets(&e, &base_assets);
     }
 }
+
+/// Load the last price of an asset, as reported by `lastprice`
+fn load_lastprice(e: &Env, asset: Asset) -> Option<PriceData> {
+    load_aggregate_lastprice(e, asset)
+        .map(|(price, _)| PriceData {
+            price: price.price,
+            timestamp: price.timestamp,
+        })
+        .ok()
+}
+
+/// Load the last price of an asset along with the sources used, as reported by
+/// `aggregate_lastprice`, and the prices of each source used
+fn load_aggregate_lastprice(
+    e: &Env,
+    asset: Asset,
+) -> Result<(AggregatePriceData, Vec<PriceData>), OracleAggregatorErrors> {
+    if is_base_asset(e, &asset) {
+        let price = base_price(e, e.ledger().timestamp());
+        return Ok((
+            AggregatePriceData {
+                price: price.price,
+                timestamp: price.timestamp,
+                sources: vec![e],
+                fallback: false,
+            },
+            vec![e],
+        ));
+    }
+
+    let configs = storage::get_asset_configs(e);
+    if let Some(config) = configs.get(asset.clone()) {
+        if storage::get_breaker(e, &asset).is_some() {
+            return Err(OracleAggregatorErrors::BreakerTripped);
+        }
+        match get_aggregate_price(e, &asset, &config) {
+            Err(error) => {
+                if is_outage_error(error) {
+                    if let Some(price) = get_fallback_price(e, &asset, &config) {
+                        return Ok((price, vec![e]));
+                    }
+                }
+                if config.circuit_breaker && is_breaker_error(error) {
+                    trip_breaker(e, asset, error);
+                }
+                Err(error)
+            }
+            result => result,
+        }
+    } else {
+        panic_with_error!(e, OracleAggregatorErrors::AssetNotFound);
+    }
+}
+
+/// Load the price of an asset for a round, as reported by `price`
+fn load_price(e: &Env, asset: Asset, timestamp: u64) -> Option<PriceData> {
+    if is_base_asset(e, &asset) {
+        return Some(base_price(e, timestamp));
+    }
+
+    if storage::get_breaker(e, &asset).is_some() {
+        return None;
+    }
+    let (oracle, config) = load_asset_config(e, asset);
+    get_price_at(e, &oracle, &config, timestamp)
+}
+
+/// Load the last N price records of an asset, as reported by `prices`
+fn load_prices(e: &Env, asset: Asset, records: u32) -> Option<Vec<PriceData>> {
+    if is_base_asset(e, &asset) {
+        if records == 0 {
+            return None;
+        }
+        return Some(vec![e, base_price(e, e.ledger().timestamp())]);
+    }
+
+    if storage::get_breaker(e, &asset).is_some() {
+        return None;
+    }
+    let (oracle, config) = load_asset_config(e, asset);
+    get_prices(e, &oracle, &config, records)
+}
+
+/// Load the time-weighted average price of an asset, as reported by `twap`
+fn load_twap(e: &Env, asset: Asset, records: u32) -> Option<i128> {
+    if is_base_asset(e, &asset) {
+        if records == 0 {
+            return None;
+        }
+        return Some(base_price(e, e.ledger().timestamp()).price);
+    }
+
+    if storage::get_breaker(e, &asset).is_some() {
+        return None;
+    }
+    let (oracle, config) = load_asset_config(e, asset);
+    get_twap(e, &oracle, &config, records)
+}
+
+/// Build the config of a newly added asset, with every setting other than its primary oracle
+/// and max_dev at its default
+fn new_asset_config(e: &Env, asset: Asset, oracle_index: u32, max_dev: u32) -> AssetConfig {
+    AssetConfig {
+        asset,
+        oracle_index,
+        max_dev,
+        max_dev_bps: 0,
+        max_dev_up_bps: 0,
+        max_dev_down_bps: 0,
+        sources: vec![e],
+        weight: 1,
+        aggregation: Aggregation::Median,
+        reference: Reference::None,
+        min_sources: 1,
+        max_mads: 0,
+        dev_rounds: 4,
+        dev_mode: DeviationMode::Previous,
+        round_tolerance: None,
+        max_step_bps: 0,
+        step_window: 0,
+        max_roc_bps: 0,
+        roc_window: 0,
+        bounds: PriceBounds::None,
+        max_age: None,
+        circuit_breaker: false,
+        grace_period: 0,
+    }
+}
+
+/// Check if the asset is the base asset or a base-like asset
+fn is_base_asset(e: &Env, asset: &Asset) -> bool {
+    let base_asset = storage::get_base(e);
+    let base_assets = storage::get_base_assets(e);
+    base_assets.contains(asset) || *asset == base_asset
+}
+
+/// Build the price of a base asset, as a fixed point number with the aggregator's decimals
+fn base_price(e: &Env, timestamp: u64) -> PriceData {
+    let decimals = storage::get_decimals(e);
+    PriceData {
+        price: 10i128.pow(decimals),
+        timestamp,
+    }
+}
+
+/// Trip the circuit breaker of an asset for the error that failed its price
+fn trip_breaker(e: &Env, asset: Asset, error: OracleAggregatorErrors) {
+    let reason = error as u32;
+    storage::set_breaker(
+        e,
+        &asset,
+        &BreakerState {
+            tripped_at: e.ledger().timestamp(),
+            reason,
+        },
+    );
+    OracleAggregatorEvents::breaker_tripped(e, asset, reason);
+}
+
+/// Check if a failure to resolve the price of an asset trips its circuit breaker
+fn is_breaker_error(error: OracleAggregatorErrors) -> bool {
+    matches!(
+        error,
+        OracleAggregatorErrors::TooManyOutliers
+            | OracleAggregatorErrors::MaxDevExceeded
+            | OracleAggregatorErrors::MaxRateOfChangeExceeded
+            | OracleAggregatorErrors::MaxDivergenceExceeded
+            | OracleAggregatorErrors::MaxStepExceeded
+    )
+}
+
+/// Check if a failure to resolve the price of an asset is due to its sources being unavailable,
+/// rather than a failed check, such that a fallback price can be reported
+fn is_outage_error(error: OracleAggregatorErrors) -> bool {
+    matches!(
+        error,
+        OracleAggregatorErrors::NoValidPrice
+            | OracleAggregatorErrors::OracleNotLive
+            | OracleAggregatorErrors::QuorumNotMet
+            | OracleAggregatorErrors::ReferenceUnavailable
+    )
+}
+
+/// Find the oracle config of a supported oracle by address
+///
+/// ### Errors
+/// * `OracleNotFound` - The oracle is not in the list of oracles
+fn find_oracle(e: &Env, oracle_id: &Address) -> OracleConfig {
+    let oracles = storage::get_oracles(e);
+    for oracle in oracles.iter() {
+        if oracle.address == *oracle_id {
+            return oracle;
+        }
+    }
+    panic_with_error!(e, OracleAggregatorErrors::OracleNotFound);
+}
+
+/// Check if an oracle is used as a source or as the reference oracle for an asset
+fn is_oracle_used(config: &AssetConfig, oracle_index: u32) -> bool {
+    config.oracle_index == oracle_index
+        || config
+            .sources
+            .iter()
+            .any(|source| source.oracle_index == oracle_index)
+        || matches!(&config.reference, Reference::Oracle(reference) if reference.oracle_index == oracle_index)
+}
+
+/// Load the asset config and the oracle config of the oracle used for the asset
+///
+/// ### Errors
+/// * `AssetNotFound` - The asset is not in the list of assets
+/// * `OracleNotFound` - The oracle is not in the list of oracles
+fn load_asset_config(e: &Env, asset: Asset) -> (OracleConfig, AssetConfig) {
+    let configs = storage::get_asset_configs(e);
+    let oracles = storage::get_oracles(e);
+    if let Some(config) = configs.get(asset) {
+        if let Some(oracle) = oracles.get(config.oracle_index) {
+            (oracle, config)
+        } else {
+            panic_with_error!(e, OracleAggregatorErrors::OracleNotFound);
+        }
+    } else {
+        panic_with_error!(e, OracleAggregatorErrors::AssetNotFound);
+    }
+}
@@ -1,6 +1,6 @@
 use soroban_sdk::contracterror;
 #[contracterror]
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum OracleAggregatorErrors {
     NotImplemented = 100,
     InvalidAssetOracle = 101,
@@ -11,4 +11,31 @@ pub enum OracleAggregatorErrors {
     OracleExists = 106,
     OracleNotFound = 107,
     MaxOraclesExceeded = 108,
+    SourceExists = 109,
+    MaxSourcesExceeded = 110,
+    InvalidWeights = 111,
+    InvalidMaxDivergence = 112,
+    InvalidMinSources = 113,
+    QuorumNotMet = 114,
+    NoValidPrice = 115,
+    MaxDivergenceExceeded = 116,
+    ReferenceUnavailable = 117,
+    InvalidDevRounds = 118,
+    InvalidMaxDev = 119,
+    InvalidWindow = 120,
+    MaxStepExceeded = 121,
+    InvalidPriceBounds = 122,
+    PriceOutOfBounds = 123,
+    NonPositivePrice = 124,
+    FutureTimestamp = 125,
+    MisalignedTimestamp = 126,
+    OracleNotLive = 127,
+    MaxDevExceeded = 128,
+    MaxRateOfChangeExceeded = 129,
+    BreakerTripped = 130,
+    BreakerNotTripped = 131,
+    MissingRound = 132,
+    TooManyOutliers = 133,
+    AlreadyMigrated = 134,
+    SourceNotFound = 135,
 }
@@ -0,0 +1,33 @@
+use soroban_sdk::{Address, Env, Symbol};
+
+use crate::types::Asset;
+
+pub struct OracleAggregatorEvents {}
+
+impl OracleAggregatorEvents {
+    /// Emitted when the circuit breaker of an asset is tripped
+    ///
+    /// - topics - `["breaker_tripped", asset: Asset]`
+    /// - data - `reason: u32`
+    ///
+    /// ### Arguments
+    /// * asset - The asset whose circuit breaker was tripped
+    /// * reason - The error code of the failure that tripped the breaker
+    pub fn breaker_tripped(e: &Env, asset: Asset, reason: u32) {
+        let topics = (Symbol::new(e, "breaker_tripped"), asset);
+        e.events().publish(topics, reason);
+    }
+
+    /// Emitted when the circuit breaker of an asset is reset
+    ///
+    /// - topics - `["breaker_reset", asset: Asset]`
+    /// - data - `guardian: Address`
+    ///
+    /// ### Arguments
+    /// * asset - The asset whose circuit breaker was reset
+    /// * guardian - The guardian that reset the breaker
+    pub fn breaker_reset(e: &Env, asset: Asset, guardian: Address) {
+        let topics = (Symbol::new(e, "breaker_reset"), asset);
+        e.events().publish(topics, guardian);
+    }
+}
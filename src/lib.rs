@@ -3,8 +3,10 @@
 #[cfg(any(test, feature = "testutils"))]
 extern crate std;
 
+mod aggregation;
 pub mod contract;
 mod errors;
+mod events;
 mod price_data;
 mod storage;
 mod types;
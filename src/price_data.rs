@@ -1,34 +1,313 @@
-use soroban_sdk::Env;
+use soroban_sdk::{panic_with_error, vec, Env, Vec, I256};
 
 use crate::{
+    aggregation::{filter_outliers, mean_price, median_price, weighted_mean_price},
+    errors::OracleAggregatorErrors,
     storage,
-    types::{AssetConfig, OracleConfig, PriceData, PriceFeedClient},
+    types::{
+        AggregatePriceData, Aggregation, Asset, AssetConfig, BoundsConfig, BoundsPolicy,
+        DeviationMode, OracleConfig, PriceBounds, PriceConfidence, PriceData, PriceFeedClient,
+        Reference, SourceConfig,
+    },
 };
 
-/// Fetch a price based on the asset config
-pub fn get_price(e: &Env, oracle: &OracleConfig, config: &AssetConfig) -> Option<PriceData> {
+/// The maximum number of rounds fetched from an oracle for the rate of change check
+const MAX_ROC_ROUNDS: u32 = 30;
+
+/// The maximum number of seconds a source timestamp can be past the ledger timestamp
+const MAX_FUTURE_DRIFT: u64 = 60;
+
+/// Fetch the last price of an asset from its source oracles, and aggregate them into a single
+/// price. Sources that cannot report a valid price, or report an outlier, are ignored.
+///
+/// Returns the aggregated price, and the prices of the sources used to calculate it
+///
+/// ### Errors
+/// * `OracleNotFound` - A source oracle is not in the list of oracles
+///
+/// ### Failures
+/// * `NoValidPrice` - No source reported a valid price
+/// * `OracleNotLive` - No source reported a valid price, and a source failed the liveness check
+/// * `NonPositivePrice`, `FutureTimestamp` or `MisalignedTimestamp` - Too few sources reported
+///   a valid price, and a source failed a sanity check
+/// * `MissingRound` - Too few sources reported a valid price, and a source skipped a round
+///   compared in the max_dev check
+/// * `MaxDevExceeded` or `MaxRateOfChangeExceeded` - Too few sources reported a valid price, and
+///   a source failed the max_dev or rate of change check
+/// * `QuorumNotMet` - Fewer than `min_sources` sources reported a valid price, and every other
+///   source was unavailable
+/// * `TooManyOutliers` - Enough sources reported a valid price, but fewer than `min_sources`
+///   remain after dropping outliers
+/// * `ReferenceUnavailable` - The reference oracle did not report a valid price
+/// * `MaxDivergenceExceeded` - The price diverged too far from the reference price
+/// * `MaxStepExceeded` - The price deviated too far from the last accepted price
+/// * `PriceOutOfBounds` - The price is out of bounds, and the bounds policy is to reject it
+pub fn get_aggregate_price(
+    e: &Env,
+    asset: &Asset,
+    config: &AssetConfig,
+) -> Result<(AggregatePriceData, Vec<PriceData>), OracleAggregatorErrors> {
+    let oracles = storage::get_oracles(e);
+    let mut prices: Vec<PriceData> = Vec::new(e);
+    let mut weights: Vec<u32> = Vec::new(e);
+    let mut used_sources: Vec<u32> = Vec::new(e);
+    // the most specific reason a source was unavailable, and the first check a source failed
+    let mut outage_error = OracleAggregatorErrors::NoValidPrice;
+    let mut check_error: Option<OracleAggregatorErrors> = None;
+    for source in all_sources(e, config).iter() {
+        if config.aggregation == Aggregation::WeightedMean && source.weight == 0 {
+            // source does not contribute to the price
+            continue;
+        }
+        let oracle = load_oracle(e, &oracles, source.oracle_index);
+        match get_price(e, &oracle, &source.asset, config) {
+            Ok(price) => {
+                prices.push_back(price);
+                weights.push_back(source.weight);
+                used_sources.push_back(source.oracle_index);
+                if config.aggregation == Aggregation::Fallback && prices.len() >= config.min_sources
+                {
+                    // enough sources have reported to use the first valid price
+                    break;
+                }
+            }
+            Err(error) => {
+                if is_source_outage(error) {
+                    if outage_error == OracleAggregatorErrors::NoValidPrice {
+                        outage_error = error;
+                    }
+                } else if check_error.is_none() {
+                    check_error = Some(error);
+                }
+            }
+        }
+    }
+    if prices.is_empty() {
+        return Err(check_error.unwrap_or(outage_error));
+    }
+    if prices.len() < config.min_sources {
+        // the quorum is only lost to an outage if no source was rejected by a check
+        return Err(check_error.unwrap_or(OracleAggregatorErrors::QuorumNotMet));
+    }
+
+    // drop outliers before aggregating, if enough sources reported a price to find them
+    if config.max_mads > 0 && prices.len() >= 3 {
+        let kept = filter_outliers(e, &prices, config.max_mads);
+        let mut kept_prices: Vec<PriceData> = Vec::new(e);
+        let mut kept_weights: Vec<u32> = Vec::new(e);
+        let mut kept_sources: Vec<u32> = Vec::new(e);
+        for index in kept.iter() {
+            kept_prices.push_back(prices.get_unchecked(index));
+            kept_weights.push_back(weights.get_unchecked(index));
+            kept_sources.push_back(used_sources.get_unchecked(index));
+        }
+        prices = kept_prices;
+        weights = kept_weights;
+        used_sources = kept_sources;
+    }
+
+    if prices.len() < config.min_sources {
+        return Err(OracleAggregatorErrors::TooManyOutliers);
+    }
+    let price = match config.aggregation {
+        Aggregation::Median => median_price(e, &prices),
+        Aggregation::WeightedMean => weighted_mean_price(&prices, &weights),
+        Aggregation::Fallback => prices.first(),
+    }
+    .ok_or(OracleAggregatorErrors::NoValidPrice)?;
+
+    // apply the hard floor and ceiling of the price, if they exist
+    let price = match &config.bounds {
+        PriceBounds::Bounded(bounds) => apply_bounds(price, bounds)?,
+        PriceBounds::None => price,
+    };
+
+    // verify the price against the reference oracle, if one exists
+    if let Reference::Oracle(reference) = &config.reference {
+        let oracle = load_oracle(e, &oracles, reference.oracle_index);
+        let reference_price = get_price(e, &oracle, &reference.asset, config)
+            .map_err(|_| OracleAggregatorErrors::ReferenceUnavailable)?;
+        if !is_within_deviation(
+            price.price,
+            reference_price.price,
+            reference.max_divergence * 100,
+        ) {
+            return Err(OracleAggregatorErrors::MaxDivergenceExceeded);
+        }
+    }
+
+    // verify the price against the step anchor, if it is within the step window
+    if config.max_step_bps > 0 {
+        if let Some(anchor) = storage::get_step_anchor(e, asset) {
+            if price.timestamp <= anchor.timestamp.saturating_add(config.step_window)
+                && !is_within_deviation(price.price, anchor.price, config.max_step_bps)
+            {
+                return Err(OracleAggregatorErrors::MaxStepExceeded);
+            }
+        }
+    }
+
+    Ok((
+        AggregatePriceData {
+            price: price.price,
+            timestamp: price.timestamp,
+            sources: used_sources,
+            fallback: false,
+        },
+        prices,
+    ))
+}
+
+/// Apply the hard floor and ceiling of an asset to a price, based on the bounds policy
+///
+/// ### Failures
+/// * `PriceOutOfBounds` - The price is out of bounds, and the bounds policy is to reject it
+fn apply_bounds(
+    price: PriceData,
+    bounds: &BoundsConfig,
+) -> Result<PriceData, OracleAggregatorErrors> {
+    if price.price >= bounds.min_price && price.price <= bounds.max_price {
+        return Ok(price);
+    }
+    match bounds.policy {
+        BoundsPolicy::Reject => Err(OracleAggregatorErrors::PriceOutOfBounds),
+        BoundsPolicy::Clamp => Ok(PriceData {
+            price: price.price.clamp(bounds.min_price, bounds.max_price),
+            timestamp: price.timestamp,
+        }),
+    }
+}
+
+/// Calculate the confidence band of an aggregated price from the prices of the sources used
+/// to calculate it. The spread is the difference between the highest and lowest source
+/// price, in basis points of the aggregated price, and saturates at `u32::MAX`.
+pub fn get_price_confidence(
+    e: &Env,
+    price: &AggregatePriceData,
+    source_prices: &Vec<PriceData>,
+) -> PriceConfidence {
+    let mut min = price.price;
+    let mut max = price.price;
+    for source_price in source_prices.iter() {
+        min = min.min(source_price.price);
+        max = max.max(source_price.price);
+    }
+    let spread = fixed_div_floor(e, max - min, price.price, 4)
+        .and_then(|spread| u32::try_from(spread).ok())
+        .unwrap_or(u32::MAX);
+    PriceConfidence {
+        price: price.price,
+        timestamp: price.timestamp,
+        min,
+        max,
+        spread,
+        sources: price.sources.len(),
+        fallback: price.fallback,
+    }
+}
+
+/// Fetch the last accepted price of an asset as a fallback price. The last accepted price can
+/// be used for up to `grace_period` seconds past the max age of the asset.
+///
+/// Returns None if the asset has no grace period, or no last accepted price within it
+pub fn get_fallback_price(
+    e: &Env,
+    asset: &Asset,
+    config: &AssetConfig,
+) -> Option<AggregatePriceData> {
+    if config.grace_period == 0 {
+        return None;
+    }
+    let last_price = storage::get_last_price(e, asset)?;
+    let max_age = get_max_age(e, config).saturating_add(config.grace_period);
+    if last_price.timestamp < e.ledger().timestamp().saturating_sub(max_age) {
+        return None;
+    }
+    Some(AggregatePriceData {
+        price: last_price.price,
+        timestamp: last_price.timestamp,
+        sources: vec![e],
+        fallback: true,
+    })
+}
+
+/// Build the list of sources for an asset, starting with the primary oracle followed by each
+/// additional source
+fn all_sources(e: &Env, config: &AssetConfig) -> Vec<SourceConfig> {
+    let mut sources = vec![
+        e,
+        SourceConfig {
+            asset: config.asset.clone(),
+            oracle_index: config.oracle_index,
+            weight: config.weight,
+        },
+    ];
+    sources.append(&config.sources);
+    sources
+}
+
+/// Fetch a price for the oracle asset from a source oracle based on the asset config
+///
+/// ### Failures
+/// * `NoValidPrice` - The source did not report a fresh price that passes the asset's checks
+/// * `NonPositivePrice` - The source reported a price of zero or less
+/// * `FutureTimestamp` - The source reported a timestamp too far past the ledger timestamp
+/// * `MisalignedTimestamp` - The source reported a timestamp not aligned to its resolution
+/// * `OracleNotLive` - The source reported a round older than its liveness rounds allow
+/// * `MissingRound` - The rounds compared for the max_dev check are not adjacent
+/// * `MaxDevExceeded` - The latest round deviated too far from the prior rounds
+/// * `MaxRateOfChangeExceeded` - The latest round moved too far over the rate of change window
+pub fn get_price(
+    e: &Env,
+    oracle: &OracleConfig,
+    asset: &Asset,
+    config: &AssetConfig,
+) -> Result<PriceData, OracleAggregatorErrors> {
     let decimals = storage::get_decimals(e);
-    let max_age = storage::get_max_age(e);
-    let oldest_timestamp = e.ledger().timestamp() - max_age;
+    let max_age = get_max_age(e, config);
+    let oldest_timestamp = e.ledger().timestamp().saturating_sub(max_age);
     let oracle_client = PriceFeedClient::new(e, &oracle.address);
     let mut price: Option<PriceData> = None;
-    if config.max_dev > 0 && config.max_dev < 100 {
-        let prices = oracle_client.prices(&config.asset, &4);
-        if let Some(prices) = prices {
-            if prices.len() >= 2 {
-                let first_price = prices.get_unchecked(0);
-                let second_price = prices.get_unchecked(1);
-                let diff = (first_price.price - second_price.price).abs();
-                let max_dev = (second_price.price * config.max_dev as i128) / 100;
-                if diff < max_dev {
-                    price = Some(first_price);
+    let (max_dev_up, max_dev_down) = max_dev_limits(config);
+    let check_dev = max_dev_up > 0 || max_dev_down > 0;
+    let check_roc = config.max_roc_bps > 0;
+    if check_dev || check_roc {
+        // fetch enough rounds for both checks with a single call
+        let mut records = 0;
+        if check_dev {
+            records = config.dev_rounds;
+        }
+        if check_roc {
+            records = records.max(rate_of_change_rounds(oracle, config));
+        }
+        let prices = oracle_client.prices(asset, &records).unwrap_or(vec![e]);
+        if let Some(latest) = prices.first() {
+            // stale rounds are not checked, as they cannot be reported
+            if latest.timestamp >= oldest_timestamp {
+                validate_source_price(e, oracle, &latest)?;
+                if check_dev {
+                    check_dev_limits(
+                        e,
+                        oracle,
+                        config,
+                        &prices.slice(0..prices.len().min(config.dev_rounds)),
+                        max_dev_up,
+                        max_dev_down,
+                    )?;
                 }
+                if check_roc && !is_within_rate_of_change(config, &prices) {
+                    return Err(OracleAggregatorErrors::MaxRateOfChangeExceeded);
+                }
+                price = Some(latest);
             }
         }
     } else {
         let round_timestamp = oracle_client.last_timestamp();
         if round_timestamp >= oldest_timestamp {
-            price = oracle_client.price(&config.asset, &round_timestamp);
+            price = oracle_client.price(asset, &round_timestamp);
+            if let Some(price) = &price {
+                validate_source_price(e, oracle, price)?;
+            }
         }
     }
 
@@ -36,10 +315,376 @@ pub fn get_price(e: &Env, oracle: &OracleConfig, config: &AssetConfig) -> Option
         // normalize the decimals and verify the timestamp returned
         normalize_price(price, &decimals, &oracle.decimals);
         if price.timestamp >= oldest_timestamp {
-            return Some(price.clone());
+            if !is_oracle_live(e, oracle, price.timestamp) {
+                return Err(OracleAggregatorErrors::OracleNotLive);
+            }
+            return Ok(price.clone());
+        }
+    }
+    Err(OracleAggregatorErrors::NoValidPrice)
+}
+
+/// Check if a source failed to report a price because it is unavailable, rather than because
+/// its price was rejected by a check
+fn is_source_outage(error: OracleAggregatorErrors) -> bool {
+    matches!(
+        error,
+        OracleAggregatorErrors::NoValidPrice | OracleAggregatorErrors::OracleNotLive
+    )
+}
+
+/// Check if the latest round of an oracle is within its liveness rounds of the ledger timestamp
+fn is_oracle_live(e: &Env, oracle: &OracleConfig, round_timestamp: u64) -> bool {
+    if oracle.liveness_rounds == 0 {
+        return true;
+    }
+    let max_round_age = oracle.liveness_rounds as u64 * oracle.resolution as u64;
+    round_timestamp >= e.ledger().timestamp().saturating_sub(max_round_age)
+}
+
+/// Validate the price reported by a source oracle is sane
+///
+/// ### Failures
+/// * `NonPositivePrice` - The price is zero or less
+/// * `FutureTimestamp` - The timestamp is more than `MAX_FUTURE_DRIFT` seconds past the ledger
+///   timestamp
+/// * `MisalignedTimestamp` - The timestamp is not a multiple of the oracle's resolution
+fn validate_source_price(
+    e: &Env,
+    oracle: &OracleConfig,
+    price: &PriceData,
+) -> Result<(), OracleAggregatorErrors> {
+    if price.price <= 0 {
+        return Err(OracleAggregatorErrors::NonPositivePrice);
+    }
+    if price.timestamp > e.ledger().timestamp().saturating_add(MAX_FUTURE_DRIFT) {
+        return Err(OracleAggregatorErrors::FutureTimestamp);
+    }
+    if oracle.resolution > 0 && !price.timestamp.is_multiple_of(oracle.resolution as u64) {
+        return Err(OracleAggregatorErrors::MisalignedTimestamp);
+    }
+    Ok(())
+}
+
+/// Check if the latest round, ordered from newest to oldest, is within the max deviation limits
+/// of the prior rounds, based on the deviation mode of the asset
+///
+/// ### Failures
+/// * `NoValidPrice` - Not enough rounds were reported
+/// * `MissingRound` - The rounds compared are further apart than the round tolerance allows
+/// * `MaxDevExceeded` - The latest round deviated too far from the prior rounds
+fn check_dev_limits(
+    e: &Env,
+    oracle: &OracleConfig,
+    config: &AssetConfig,
+    rounds: &Vec<PriceData>,
+    max_dev_up: u32,
+    max_dev_down: u32,
+) -> Result<(), OracleAggregatorErrors> {
+    if rounds.len() < 2 {
+        return Err(OracleAggregatorErrors::NoValidPrice);
+    }
+    let compared_rounds = match config.dev_mode {
+        DeviationMode::Previous => 2,
+        _ => rounds.len(),
+    };
+    if let Some(tolerance) = config.round_tolerance {
+        let max_gap = oracle.resolution as u64 + tolerance as u64;
+        if has_round_gap(&rounds.slice(0..compared_rounds), max_gap) {
+            return Err(OracleAggregatorErrors::MissingRound);
+        }
+    }
+
+    let first_price = rounds.get_unchecked(0);
+    let prior_prices = rounds.slice(1..compared_rounds);
+    let prior_price = match config.dev_mode {
+        DeviationMode::Previous => prior_prices.first(),
+        DeviationMode::Median => median_price(e, &prior_prices),
+        DeviationMode::Mean => mean_price(&prior_prices),
+    };
+    let prior_price = prior_price.ok_or(OracleAggregatorErrors::NoValidPrice)?;
+    // a price moving up is checked against the upward limit, and a price
+    // moving down against the downward limit
+    let max_dev = if first_price.price >= prior_price.price {
+        max_dev_up
+    } else {
+        max_dev_down
+    };
+    if max_dev > 0 && !is_within_deviation(first_price.price, prior_price.price, max_dev) {
+        return Err(OracleAggregatorErrors::MaxDevExceeded);
+    }
+    Ok(())
+}
+
+/// The number of rounds needed from an oracle to cover the rate of change window of an asset
+fn rate_of_change_rounds(oracle: &OracleConfig, config: &AssetConfig) -> u32 {
+    let rounds = config.roc_window / (oracle.resolution.max(1) as u64) + 1;
+    rounds.min(u32::MAX as u64) as u32
+}
+
+/// Check if the rate of change window of an asset can be covered by at most `MAX_ROC_ROUNDS`
+/// rounds of an oracle. Always true if the rate of change is not checked.
+pub fn is_valid_roc_window(oracle: &OracleConfig, config: &AssetConfig) -> bool {
+    config.max_roc_bps == 0 || rate_of_change_rounds(oracle, config) <= MAX_ROC_ROUNDS
+}
+
+/// Check if the latest round, ordered from newest to oldest, has moved less than `max_roc_bps`
+/// from every round within `roc_window` seconds of it
+fn is_within_rate_of_change(config: &AssetConfig, rounds: &Vec<PriceData>) -> bool {
+    let latest = rounds.get_unchecked(0);
+    let window_start = latest.timestamp.saturating_sub(config.roc_window);
+    for round in rounds.iter().skip(1) {
+        if round.timestamp < window_start {
+            break;
+        }
+        if !is_within_deviation(latest.price, round.price, config.max_roc_bps) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Check if any consecutive rounds, ordered from newest to oldest, are more than `max_gap`
+/// seconds apart
+fn has_round_gap(rounds: &Vec<PriceData>, max_gap: u64) -> bool {
+    for index in 1..rounds.len() {
+        let newer = rounds.get_unchecked(index - 1);
+        let older = rounds.get_unchecked(index);
+        if newer.timestamp.abs_diff(older.timestamp) > max_gap {
+            return true;
+        }
+    }
+    false
+}
+
+/// Get the maximum deviation allowed for a stable price of an asset, in basis points. If
+/// `max_dev_bps` is not set, `max_dev` is converted from a percentage to basis points.
+///
+/// Returns 0 if the max_dev check is disabled
+fn max_dev_bps(config: &AssetConfig) -> u32 {
+    if config.max_dev_bps > 0 {
+        config.max_dev_bps
+    } else if config.max_dev > 0 && config.max_dev < 100 {
+        config.max_dev * 100
+    } else {
+        0
+    }
+}
+
+/// Get the maximum upward and downward deviation allowed for a stable price of an asset, in
+/// basis points. A direction without its own limit uses the symmetric limit from `max_dev_bps`.
+///
+/// Returns 0 for a direction that is not checked
+fn max_dev_limits(config: &AssetConfig) -> (u32, u32) {
+    let max_dev = max_dev_bps(config);
+    let max_dev_up = if config.max_dev_up_bps > 0 {
+        config.max_dev_up_bps
+    } else {
+        max_dev
+    };
+    let max_dev_down = if config.max_dev_down_bps > 0 {
+        config.max_dev_down_bps
+    } else {
+        max_dev
+    };
+    (max_dev_up, max_dev_down)
+}
+
+/// Check if a price deviates from a reference price by less than `max_dev_bps`, in basis points
+fn is_within_deviation(price: i128, reference_price: i128, max_dev_bps: u32) -> bool {
+    let diff = (price - reference_price).abs();
+    let max_diff = (reference_price * max_dev_bps as i128) / 10000;
+    diff < max_diff
+}
+
+/// Load an oracle config by index
+///
+/// ### Errors
+/// * `OracleNotFound` - The oracle is not in the list of oracles
+fn load_oracle(e: &Env, oracles: &Vec<OracleConfig>, index: u32) -> OracleConfig {
+    oracles
+        .get(index)
+        .unwrap_or_else(|| panic_with_error!(e, OracleAggregatorErrors::OracleNotFound))
+}
+
+/// Fetch the price for a specific round based on the asset config
+///
+/// Returns None if the source oracle has no price for the round, or the price fails the source
+/// sanity checks
+pub fn get_price_at(
+    e: &Env,
+    oracle: &OracleConfig,
+    config: &AssetConfig,
+    timestamp: u64,
+) -> Option<PriceData> {
+    let decimals = storage::get_decimals(e);
+    let oracle_client = PriceFeedClient::new(e, &oracle.address);
+    let mut price = oracle_client.price(&config.asset, &timestamp)?;
+    validate_source_price(e, oracle, &price).ok()?;
+    normalize_price(&mut price, &decimals, &oracle.decimals);
+    Some(price)
+}
+
+/// Fetch the last `records` rounds of prices based on the asset config. Any round older than
+/// `max_age * records` seconds, or that fails the source sanity checks, is filtered out.
+pub fn get_prices(
+    e: &Env,
+    oracle: &OracleConfig,
+    config: &AssetConfig,
+    records: u32,
+) -> Option<Vec<PriceData>> {
+    let oldest_timestamp = oldest_round_timestamp(e, config, records);
+    let prices = fetch_prices(e, oracle, config, records)?;
+
+    let mut result: Vec<PriceData> = Vec::new(e);
+    for price in prices.iter() {
+        if price.timestamp >= oldest_timestamp {
+            result.push_back(price);
+        }
+    }
+    if result.is_empty() {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+/// Calculate the time-weighted average price over the last `records` rounds based on the asset
+/// config.
+///
+/// Returns None if the source oracle did not report `records` contiguous rounds based on the
+/// oracle's resolution, if any round is older than `max_age * records` seconds, or if any round
+/// fails the source sanity checks
+pub fn get_twap(
+    e: &Env,
+    oracle: &OracleConfig,
+    config: &AssetConfig,
+    records: u32,
+) -> Option<i128> {
+    let oldest_timestamp = oldest_round_timestamp(e, config, records);
+    let prices = fetch_prices(e, oracle, config, records)?;
+    if prices.len() != records {
+        return None;
+    }
+
+    let mut sum: i128 = 0;
+    let mut prev_timestamp: Option<u64> = None;
+    for price in prices.iter() {
+        if price.timestamp < oldest_timestamp {
+            return None;
+        }
+        if let Some(prev_timestamp) = prev_timestamp {
+            if prev_timestamp.checked_sub(price.timestamp) != Some(oracle.resolution as u64) {
+                return None;
+            }
+        }
+        prev_timestamp = Some(price.timestamp);
+        sum += price.price;
+    }
+    Some(sum / records as i128)
+}
+
+/// Fetch the last `records` rounds of prices from the source oracle, normalized to the
+/// aggregator's decimals. Rounds that fail the source sanity checks are dropped.
+fn fetch_prices(
+    e: &Env,
+    oracle: &OracleConfig,
+    config: &AssetConfig,
+    records: u32,
+) -> Option<Vec<PriceData>> {
+    if records == 0 {
+        return None;
+    }
+    let decimals = storage::get_decimals(e);
+    let oracle_client = PriceFeedClient::new(e, &oracle.address);
+    let prices = oracle_client.prices(&config.asset, &records)?;
+
+    let mut result: Vec<PriceData> = Vec::new(e);
+    for mut price in prices.iter() {
+        if validate_source_price(e, oracle, &price).is_err() {
+            continue;
         }
+        normalize_price(&mut price, &decimals, &oracle.decimals);
+        result.push_back(price);
+    }
+    Some(result)
+}
+
+/// The max age of a price of an asset, in seconds. This is the asset's max age if one is set,
+/// otherwise the max age of the oracle aggregator.
+fn get_max_age(e: &Env, config: &AssetConfig) -> u64 {
+    config.max_age.unwrap_or_else(|| storage::get_max_age(e))
+}
+
+/// The oldest round timestamp allowed when fetching `records` rounds of prices
+fn oldest_round_timestamp(e: &Env, config: &AssetConfig, records: u32) -> u64 {
+    let max_age = get_max_age(e, config);
+    e.ledger()
+        .timestamp()
+        .saturating_sub(max_age * records as u64)
+}
+
+/// Calculate the price of the base asset quoted in the quote asset, as a fixed point number
+/// with `decimals` decimals. The timestamp of the older price is reported.
+///
+/// Returns None if the quote price is not positive or the cross price does not fit in an i128
+pub fn cross_price(
+    e: &Env,
+    base_price: &PriceData,
+    quote_price: &PriceData,
+    decimals: u32,
+) -> Option<PriceData> {
+    Some(PriceData {
+        price: fixed_div_floor(e, base_price.price, quote_price.price, decimals)?,
+        timestamp: base_price.timestamp.min(quote_price.timestamp),
+    })
+}
+
+/// Calculate `x * 10^decimals / y`, rounding down
+///
+/// Returns None if `y` is not positive or the result does not fit in an i128
+pub fn fixed_div_floor(e: &Env, x: i128, y: i128, decimals: u32) -> Option<i128> {
+    if y <= 0 {
+        return None;
+    }
+    let scalar = 10i128.pow(decimals);
+    match x.checked_mul(scalar) {
+        Some(scaled_x) => Some(scaled_x / y),
+        None => {
+            // the scaled value does not fit in an i128, do the math in 256 bits
+            I256::from_i128(e, x)
+                .mul(&I256::from_i128(e, scalar))
+                .div(&I256::from_i128(e, y))
+                .to_i128()
+        }
+    }
+}
+
+/// Calculate the cross prices for each round reported for both the base and quote asset
+///
+/// Returns None if no rounds could be calculated
+pub fn cross_prices(
+    e: &Env,
+    base_prices: &Vec<PriceData>,
+    quote_prices: &Vec<PriceData>,
+    decimals: u32,
+) -> Option<Vec<PriceData>> {
+    let mut result: Vec<PriceData> = Vec::new(e);
+    for base_price in base_prices.iter() {
+        let quote_price = quote_prices
+            .iter()
+            .find(|quote_price| quote_price.timestamp == base_price.timestamp);
+        if let Some(quote_price) = quote_price {
+            if let Some(price) = cross_price(e, &base_price, &quote_price, decimals) {
+                result.push_back(price);
+            }
+        }
+    }
+    if result.is_empty() {
+        None
+    } else {
+        Some(result)
     }
-    return None;
 }
 
 /// Normalize the price data to the correct number of decimals
@@ -58,6 +703,244 @@ fn normalize_price(price_data: &mut PriceData, decimals: &u32, oracle_decimals:
 mod test {
     use super::*;
 
+    #[test]
+    fn test_is_within_deviation() {
+        // 0.3% of 1.0 => max diff of 0.003
+        assert!(is_within_deviation(1_0029999, 1_0000000, 30));
+        assert!(!is_within_deviation(1_0030000, 1_0000000, 30));
+        assert!(is_within_deviation(0_9970001, 1_0000000, 30));
+        assert!(!is_within_deviation(0_9970000, 1_0000000, 30));
+
+        // 1 bps
+        assert!(is_within_deviation(1_0000999, 1_0000000, 1));
+        assert!(!is_within_deviation(1_0001000, 1_0000000, 1));
+
+        // max diff rounds down
+        assert!(is_within_deviation(1004, 1000, 50));
+        assert!(!is_within_deviation(1005, 1000, 50));
+        assert!(!is_within_deviation(1000, 1000, 5));
+    }
+
+    fn test_config(e: &Env) -> AssetConfig {
+        AssetConfig {
+            asset: Asset::Other(soroban_sdk::Symbol::new(e, "TEST")),
+            oracle_index: 0,
+            max_dev: 0,
+            max_dev_bps: 0,
+            max_dev_up_bps: 0,
+            max_dev_down_bps: 0,
+            sources: vec![e],
+            weight: 1,
+            aggregation: Aggregation::Median,
+            reference: Reference::None,
+            min_sources: 1,
+            max_mads: 0,
+            dev_rounds: 4,
+            dev_mode: DeviationMode::Previous,
+            round_tolerance: None,
+            max_step_bps: 0,
+            step_window: 0,
+            max_roc_bps: 0,
+            roc_window: 0,
+            bounds: PriceBounds::None,
+            max_age: None,
+            circuit_breaker: false,
+            grace_period: 0,
+        }
+    }
+
+    #[test]
+    fn test_max_dev_bps() {
+        let e = Env::default();
+        let mut config = test_config(&e);
+        config.max_dev = 5;
+        assert_eq!(max_dev_bps(&config), 500);
+
+        config.max_dev = 100;
+        assert_eq!(max_dev_bps(&config), 0);
+
+        config.max_dev = 0;
+        assert_eq!(max_dev_bps(&config), 0);
+
+        config.max_dev_bps = 30;
+        assert_eq!(max_dev_bps(&config), 30);
+
+        config.max_dev = 5;
+        assert_eq!(max_dev_bps(&config), 30);
+    }
+
+    #[test]
+    fn test_max_dev_limits() {
+        let e = Env::default();
+        let mut config = test_config(&e);
+        assert_eq!(max_dev_limits(&config), (0, 0));
+
+        config.max_dev_up_bps = 50;
+        assert_eq!(max_dev_limits(&config), (50, 0));
+
+        config.max_dev = 2;
+        assert_eq!(max_dev_limits(&config), (50, 200));
+
+        config.max_dev_bps = 100;
+        config.max_dev_down_bps = 300;
+        assert_eq!(max_dev_limits(&config), (50, 300));
+    }
+
+    #[test]
+    fn test_rate_of_change() {
+        let e = Env::default();
+        let mut config = test_config(&e);
+        config.max_roc_bps = 1000;
+        config.roc_window = 3600;
+
+        let oracle = OracleConfig {
+            address: soroban_sdk::Address::from_str(
+                &e,
+                "CAS3J7GYLGXMF6TDJBBYYSE3HQ6BBSMLNUQ34T6TZMYMW2EVH34XOWMA",
+            ),
+            index: 0,
+            resolution: 300,
+            decimals: 7,
+            liveness_rounds: 0,
+        };
+        assert_eq!(rate_of_change_rounds(&oracle, &config), 13);
+        assert!(is_valid_roc_window(&oracle, &config));
+        config.roc_window = 86400;
+        assert_eq!(rate_of_change_rounds(&oracle, &config), 289);
+        assert!(!is_valid_roc_window(&oracle, &config));
+        config.roc_window = 8700;
+        assert_eq!(rate_of_change_rounds(&oracle, &config), 30);
+        assert!(is_valid_roc_window(&oracle, &config));
+        config.roc_window = 600;
+        assert_eq!(rate_of_change_rounds(&oracle, &config), 3);
+
+        let rounds = vec![
+            &e,
+            PriceData {
+                price: 1_0900000,
+                timestamp: 1200,
+            },
+            PriceData {
+                price: 1_0500000,
+                timestamp: 900,
+            },
+            PriceData {
+                price: 1_0000000,
+                timestamp: 600,
+            },
+            PriceData {
+                price: 0_9000000,
+                timestamp: 300,
+            },
+        ];
+        // 9% from 1.0 at the start of the window, the 0.9 round is outside it
+        assert!(is_within_rate_of_change(&config, &rounds));
+
+        // 21.1% from 0.9
+        config.roc_window = 900;
+        assert!(!is_within_rate_of_change(&config, &rounds));
+
+        // a single round has not moved
+        assert!(is_within_rate_of_change(&config, &rounds.slice(0..1)));
+
+        // missing rounds are ignored, 9% from 1.0
+        config.roc_window = 900;
+        let mut gap_rounds = rounds.clone();
+        gap_rounds.remove(1);
+        gap_rounds.set(
+            2,
+            PriceData {
+                price: 1_1000000,
+                timestamp: 300,
+            },
+        );
+        assert!(is_within_rate_of_change(&config, &gap_rounds));
+    }
+
+    #[test]
+    fn test_validate_source_price() {
+        use soroban_sdk::testutils::Ledger;
+
+        let e = Env::default();
+        e.ledger().set_timestamp(1000);
+        let oracle = OracleConfig {
+            address: soroban_sdk::Address::from_str(
+                &e,
+                "CAS3J7GYLGXMF6TDJBBYYSE3HQ6BBSMLNUQ34T6TZMYMW2EVH34XOWMA",
+            ),
+            index: 0,
+            resolution: 300,
+            decimals: 7,
+            liveness_rounds: 0,
+        };
+        let mut price = PriceData {
+            price: 1_0000000,
+            timestamp: 900,
+        };
+        assert!(validate_source_price(&e, &oracle, &price).is_ok());
+
+        // non-positive prices
+        price.price = 0;
+        let result = validate_source_price(&e, &oracle, &price);
+        assert_eq!(result.err(), Some(OracleAggregatorErrors::NonPositivePrice));
+        price.price = -1;
+        let result = validate_source_price(&e, &oracle, &price);
+        assert_eq!(result.err(), Some(OracleAggregatorErrors::NonPositivePrice));
+        price.price = 1;
+
+        // timestamps up to 60s past the ledger timestamp are allowed
+        e.ledger().set_timestamp(1140);
+        price.timestamp = 1200;
+        assert!(validate_source_price(&e, &oracle, &price).is_ok());
+        e.ledger().set_timestamp(1139);
+        let result = validate_source_price(&e, &oracle, &price);
+        assert_eq!(result.err(), Some(OracleAggregatorErrors::FutureTimestamp));
+
+        // timestamps must be aligned to the resolution
+        price.timestamp = 907;
+        let result = validate_source_price(&e, &oracle, &price);
+        assert_eq!(
+            result.err(),
+            Some(OracleAggregatorErrors::MisalignedTimestamp)
+        );
+        price.timestamp = 600;
+        assert!(validate_source_price(&e, &oracle, &price).is_ok());
+    }
+
+    #[test]
+    fn test_apply_bounds() {
+        let price = PriceData {
+            price: 1_0500000,
+            timestamp: 600,
+        };
+        let mut bounds = BoundsConfig {
+            min_price: 0_9000000,
+            max_price: 1_1000000,
+            policy: BoundsPolicy::Reject,
+        };
+        let result = apply_bounds(price.clone(), &bounds).unwrap();
+        assert_eq!(result.price, 1_0500000);
+
+        // bounds are inclusive
+        bounds.max_price = 1_0500000;
+        let result = apply_bounds(price.clone(), &bounds).unwrap();
+        assert_eq!(result.price, 1_0500000);
+
+        bounds.max_price = 1_0499999;
+        let result = apply_bounds(price.clone(), &bounds);
+        assert_eq!(result.err(), Some(OracleAggregatorErrors::PriceOutOfBounds));
+
+        bounds.policy = BoundsPolicy::Clamp;
+        let result = apply_bounds(price.clone(), &bounds).unwrap();
+        assert_eq!(result.price, 1_0499999);
+        assert_eq!(result.timestamp, 600);
+
+        bounds.min_price = 1_0600000;
+        bounds.max_price = 1_1000000;
+        let result = apply_bounds(price.clone(), &bounds).unwrap();
+        assert_eq!(result.price, 1_0600000);
+    }
+
     #[test]
     fn test_normalize_price() {
         let price = PriceData {
@@ -81,4 +964,77 @@ mod test {
         normalize_price(&mut price_4, &2, &7);
         assert_eq!(price_4.price, 123);
     }
+
+    #[test]
+    fn test_cross_price() {
+        let e = Env::default();
+        let base_price = PriceData {
+            price: 0_3000000,
+            timestamp: 100,
+        };
+        let quote_price = PriceData {
+            price: 1_2000000,
+            timestamp: 200,
+        };
+
+        let price = cross_price(&e, &base_price, &quote_price, 7).unwrap();
+        assert_eq!(price.price, 0_2500000);
+        assert_eq!(price.timestamp, 100);
+
+        let price = cross_price(&e, &quote_price, &base_price, 7).unwrap();
+        assert_eq!(price.price, 4_0000000);
+        assert_eq!(price.timestamp, 100);
+
+        // rounds down
+        let quote_price_3 = PriceData {
+            price: 3_0000000,
+            timestamp: 100,
+        };
+        let price = cross_price(&e, &base_price, &quote_price_3, 7).unwrap();
+        assert_eq!(price.price, 0_1000000);
+        let price = cross_price(&e, &quote_price_3, &quote_price_3, 7).unwrap();
+        assert_eq!(price.price, 1_0000000);
+        let price = cross_price(
+            &e,
+            &PriceData {
+                price: 2_0000000,
+                timestamp: 100,
+            },
+            &quote_price_3,
+            7,
+        )
+        .unwrap();
+        assert_eq!(price.price, 0_6666666);
+
+        // non-positive quote prices cannot be used
+        let zero_price = PriceData {
+            price: 0,
+            timestamp: 100,
+        };
+        assert!(cross_price(&e, &base_price, &zero_price, 7).is_none());
+    }
+
+    #[test]
+    fn test_cross_price_large_values() {
+        let e = Env::default();
+        // scaling by 18 decimals overflows an i128
+        let base_price = PriceData {
+            price: 1_000_000_000_000_000_000_000_000,
+            timestamp: 100,
+        };
+        let quote_price = PriceData {
+            price: 2_000_000_000_000_000_000,
+            timestamp: 100,
+        };
+
+        let price = cross_price(&e, &base_price, &quote_price, 18).unwrap();
+        assert_eq!(price.price, 500_000_000_000_000_000_000_000);
+
+        // result does not fit in an i128
+        let quote_price = PriceData {
+            price: 1,
+            timestamp: 100,
+        };
+        assert!(cross_price(&e, &base_price, &quote_price, 18).is_none());
+    }
 }
@@ -1,18 +1,40 @@
-use crate::types::{Asset, AssetConfig, OracleConfig};
-use soroban_sdk::{map, unwrap::UnwrapOptimized, vec, Address, Env, Map, Symbol, Vec};
+use crate::types::{
+    Asset, AssetConfig, BreakerState, LegacyAssetConfig, LegacyOracleConfig, OracleConfig,
+    PriceData,
+};
+use soroban_sdk::{
+    contracttype, map, unwrap::UnwrapOptimized, vec, Address, Env, Map, Symbol, Vec,
+};
 
 const ADMIN_KEY: &str = "Admin";
+const GUARDIAN_KEY: &str = "Guardian";
 const BASE_KEY: &str = "Base";
 const BASE_ASSETS_KEY: &str = "BaseAssets";
 const ASSETS_KEY: &str = "Assets";
 const ORACLES_KEY: &str = "Oracles";
 const DECIMALS_KEY: &str = "Decimals";
 const MAX_AGE_KEY: &str = "MaxAge";
+const VERSION_KEY: &str = "Version";
+
+/// The version of the storage layout. Storage written before the layout was versioned is
+/// version 1.
+pub const STORAGE_VERSION: u32 = 2;
 
 const ONE_DAY_LEDGERS: u32 = 17280; // assumes 5 seconds per ledger on average
 const LEDGER_THRESHOLD: u32 = 30 * ONE_DAY_LEDGERS;
 const LEDGER_BUMP: u32 = 31 * ONE_DAY_LEDGERS;
 
+#[derive(Clone)]
+#[contracttype]
+pub enum AssetDataKey {
+    // The last accepted price of an asset
+    LastPrice(Asset),
+    // The last accepted price the max_step check of an asset is anchored to
+    StepAnchor(Asset),
+    // The state of the tripped circuit breaker of an asset
+    Breaker(Asset),
+}
+
 //********** Storage Utils **********//
 
 /// Bump the instance lifetime by the defined amount
@@ -39,6 +61,37 @@ pub fn get_admin(e: &Env) -> Address {
         .unwrap_optimized()
 }
 
+/// Set the version of the storage layout
+pub fn set_version(e: &Env, version: &u32) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u32>(&Symbol::new(e, VERSION_KEY), version);
+}
+
+/// Get the version of the storage layout, or 1 if the storage was written before the layout
+/// was versioned
+pub fn get_version(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get::<Symbol, u32>(&Symbol::new(e, VERSION_KEY))
+        .unwrap_or(1)
+}
+
+/// Set the guardian address
+pub fn set_guardian(e: &Env, guardian: &Address) {
+    e.storage()
+        .instance()
+        .set::<Symbol, Address>(&Symbol::new(e, GUARDIAN_KEY), guardian);
+}
+
+/// Get the guardian address. Defaults to the admin if no guardian is set.
+pub fn get_guardian(e: &Env) -> Address {
+    e.storage()
+        .instance()
+        .get::<Symbol, Address>(&Symbol::new(e, GUARDIAN_KEY))
+        .unwrap_or_else(|| get_admin(e))
+}
+
 /// Set the max age of a price, in seconds
 pub fn set_max_age(e: &Env, max_age: &u64) {
     e.storage()
@@ -114,6 +167,14 @@ pub fn get_oracles(e: &Env) -> Vec<OracleConfig> {
         .unwrap_or(vec![&e])
 }
 
+/// Get the list of oracles stored in the layout of the first version of the oracle aggregator
+pub fn get_legacy_oracles(e: &Env) -> Vec<LegacyOracleConfig> {
+    e.storage()
+        .instance()
+        .get::<Symbol, Vec<LegacyOracleConfig>>(&Symbol::new(e, ORACLES_KEY))
+        .unwrap_or(vec![&e])
+}
+
 /// Set an asset configuration
 pub fn set_asset_configs(e: &Env, config: &Map<Asset, AssetConfig>) {
     e.storage()
@@ -121,6 +182,15 @@ pub fn set_asset_configs(e: &Env, config: &Map<Asset, AssetConfig>) {
         .set::<Symbol, Map<Asset, AssetConfig>>(&Symbol::new(e, ASSETS_KEY), config);
 }
 
+/// Get the asset configurations stored in the layout of the first version of the oracle
+/// aggregator
+pub fn get_legacy_asset_configs(e: &Env) -> Map<Asset, LegacyAssetConfig> {
+    e.storage()
+        .instance()
+        .get::<Symbol, Map<Asset, LegacyAssetConfig>>(&Symbol::new(e, ASSETS_KEY))
+        .unwrap_or(map![&e])
+}
+
 /// Get an asset configuration
 pub fn get_asset_configs(e: &Env) -> Map<Asset, AssetConfig> {
     e.storage()
@@ -128,3 +198,66 @@ pub fn get_asset_configs(e: &Env) -> Map<Asset, AssetConfig> {
         .get::<Symbol, Map<Asset, AssetConfig>>(&Symbol::new(e, ASSETS_KEY))
         .unwrap_or(map![&e])
 }
+
+/********** Persistent **********/
+
+/// Set the last accepted price of an asset
+pub fn set_last_price(e: &Env, asset: &Asset, price: &PriceData) {
+    let key = AssetDataKey::LastPrice(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<AssetDataKey, PriceData>(&key, price);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+/// Get the last accepted price of an asset
+pub fn get_last_price(e: &Env, asset: &Asset) -> Option<PriceData> {
+    e.storage()
+        .persistent()
+        .get::<AssetDataKey, PriceData>(&AssetDataKey::LastPrice(asset.clone()))
+}
+
+/// Set the last accepted price the max_step check of an asset is anchored to
+pub fn set_step_anchor(e: &Env, asset: &Asset, price: &PriceData) {
+    let key = AssetDataKey::StepAnchor(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<AssetDataKey, PriceData>(&key, price);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+/// Get the last accepted price the max_step check of an asset is anchored to
+pub fn get_step_anchor(e: &Env, asset: &Asset) -> Option<PriceData> {
+    e.storage()
+        .persistent()
+        .get::<AssetDataKey, PriceData>(&AssetDataKey::StepAnchor(asset.clone()))
+}
+
+/// Set the state of the tripped circuit breaker of an asset
+pub fn set_breaker(e: &Env, asset: &Asset, state: &BreakerState) {
+    let key = AssetDataKey::Breaker(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<AssetDataKey, BreakerState>(&key, state);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD, LEDGER_BUMP);
+}
+
+/// Get the state of the circuit breaker of an asset, or None if it is not tripped
+pub fn get_breaker(e: &Env, asset: &Asset) -> Option<BreakerState> {
+    e.storage()
+        .persistent()
+        .get::<AssetDataKey, BreakerState>(&AssetDataKey::Breaker(asset.clone()))
+}
+
+/// Remove the state of the circuit breaker of an asset
+pub fn remove_breaker(e: &Env, asset: &Asset) {
+    e.storage()
+        .persistent()
+        .remove::<AssetDataKey>(&AssetDataKey::Breaker(asset.clone()));
+}
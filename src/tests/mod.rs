@@ -1,4 +1,12 @@
 mod snapshot;
 mod test_admin;
+mod test_breaker;
+mod test_cross_price;
 mod test_lastprice;
+mod test_migrate;
+mod test_poke;
+mod test_price;
 mod test_reflector;
+mod test_sep40;
+mod test_sources;
+mod test_twap;
@@ -10,7 +10,7 @@
  * BTCLN,AQUA,yUSDC,FIDR,SSLX,ARST,EURC,XLM,XRP,EURC,XRF,USDGLO,CETES,USTRY
  */
 
-use soroban_sdk::{testutils::EnvTestConfig, Env};
+use soroban_sdk::{testutils::EnvTestConfig, vec, Address, Env, IntoVal, Symbol, Val, Vec};
 
 pub const LAST_UPDATE_TIMESTAMP: u64 = 1744044900;
 
@@ -29,3 +29,35 @@ pub fn env_from_snapshot() -> Env {
     });
     env
 }
+
+/// Set prices on the reflector contract for the most recent round based on the
+/// current ledger timestamp.
+pub fn set_reflector_prices(
+    e: &Env,
+    xlm_price: i128,
+    eurc_price: i128,
+    aqua_price: i128,
+    usdglo_price: i128,
+) {
+    let timestamp: u64 = (e.ledger().timestamp() / 300u64) * 300u64 * 1000u64;
+    let reflector = Address::from_str(e, REFLECTOR);
+    let price_array: Vec<i128> = vec![
+        e,
+        1, // BTCLN
+        aqua_price,
+        1, // yUSDC
+        1, // FIDR
+        1, // SSLX
+        1, // ARST
+        1, // mykobo EURC
+        xlm_price,
+        1, // XRP
+        eurc_price,
+        1, // XRF
+        usdglo_price,
+        1, // CETES
+        1, // USTRY
+    ];
+    let args: Vec<Val> = vec![e, price_array.into_val(e), timestamp.into_val(e)];
+    e.invoke_contract::<Val>(&reflector, &Symbol::new(e, "set_price"), args);
+}
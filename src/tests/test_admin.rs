@@ -5,7 +5,7 @@ use crate::{
         assert_asset_config_equal, assert_assets_equal, assert_oracle_config_equal,
         create_oracle_aggregator, EnvTestUtils,
     },
-    types::{Asset, AssetConfig, OracleConfig},
+    types::{Aggregation, Asset, AssetConfig, DeviationMode, OracleConfig, PriceBounds, Reference},
 };
 use sep_40_oracle::testutils::{Asset as MockAsset, MockPriceOracleClient, MockPriceOracleWASM};
 
@@ -110,6 +110,7 @@ fn test_admin_actions() {
             index: 0,
             resolution: 300,
             decimals: 9,
+            liveness_rounds: 0,
         },
     );
 
@@ -155,6 +156,26 @@ fn test_admin_actions() {
             asset: Asset::Stellar(asset_0.clone()),
             oracle_index: 0,
             max_dev: 0,
+            max_dev_bps: 0,
+            max_dev_up_bps: 0,
+            max_dev_down_bps: 0,
+            sources: vec![&e],
+            weight: 1,
+            aggregation: Aggregation::Median,
+            reference: Reference::None,
+            min_sources: 1,
+            max_mads: 0,
+            dev_rounds: 4,
+            dev_mode: DeviationMode::Previous,
+            round_tolerance: None,
+            max_step_bps: 0,
+            step_window: 0,
+            max_roc_bps: 0,
+            roc_window: 0,
+            bounds: PriceBounds::None,
+            max_age: None,
+            circuit_breaker: false,
+            grace_period: 0,
         },
     );
 
@@ -181,6 +202,26 @@ fn test_admin_actions() {
             asset: Asset::Stellar(asset_1.clone()),
             oracle_index: 0,
             max_dev: 15,
+            max_dev_bps: 0,
+            max_dev_up_bps: 0,
+            max_dev_down_bps: 0,
+            sources: vec![&e],
+            weight: 1,
+            aggregation: Aggregation::Median,
+            reference: Reference::None,
+            min_sources: 1,
+            max_mads: 0,
+            dev_rounds: 4,
+            dev_mode: DeviationMode::Previous,
+            round_tolerance: None,
+            max_step_bps: 0,
+            step_window: 0,
+            max_roc_bps: 0,
+            roc_window: 0,
+            bounds: PriceBounds::None,
+            max_age: None,
+            circuit_breaker: false,
+            grace_period: 0,
         },
     );
 
@@ -207,6 +248,7 @@ fn test_admin_actions() {
             index: 1,
             resolution: 600,
             decimals: 6,
+            liveness_rounds: 0,
         },
     );
 
@@ -234,6 +276,26 @@ fn test_admin_actions() {
                 asset: asset.clone(),
                 oracle_index: 0,
                 max_dev: 0,
+                max_dev_bps: 0,
+                max_dev_up_bps: 0,
+                max_dev_down_bps: 0,
+                sources: vec![&e],
+                weight: 1,
+                aggregation: Aggregation::Median,
+                reference: Reference::None,
+                min_sources: 1,
+                max_mads: 0,
+                dev_rounds: 4,
+                dev_mode: DeviationMode::Previous,
+                round_tolerance: None,
+                max_step_bps: 0,
+                step_window: 0,
+                max_roc_bps: 0,
+                roc_window: 0,
+                bounds: PriceBounds::None,
+                max_age: None,
+                circuit_breaker: false,
+                grace_period: 0,
             };
             assets.set(asset, asset_config);
         }
@@ -276,6 +338,26 @@ fn test_admin_actions() {
             asset: Asset::Other(symbol_2.clone()),
             oracle_index: 1,
             max_dev: 0,
+            max_dev_bps: 0,
+            max_dev_up_bps: 0,
+            max_dev_down_bps: 0,
+            sources: vec![&e],
+            weight: 1,
+            aggregation: Aggregation::Median,
+            reference: Reference::None,
+            min_sources: 1,
+            max_mads: 0,
+            dev_rounds: 4,
+            dev_mode: DeviationMode::Previous,
+            round_tolerance: None,
+            max_step_bps: 0,
+            step_window: 0,
+            max_roc_bps: 0,
+            roc_window: 0,
+            bounds: PriceBounds::None,
+            max_age: None,
+            circuit_breaker: false,
+            grace_period: 0,
         },
     );
 
@@ -417,6 +499,7 @@ fn test_oracles() {
             index: 0,
             resolution: 300,
             decimals: 9,
+            liveness_rounds: 0,
         },
     );
 
@@ -429,6 +512,7 @@ fn test_oracles() {
                 index: i,
                 resolution: 0,
                 decimals: 0,
+                liveness_rounds: 0,
             });
         }
         storage::set_oracles(&e, &temp_oracles);
@@ -452,6 +536,7 @@ fn test_oracles() {
             index: 1,
             resolution: 600,
             decimals: 6,
+            liveness_rounds: 0,
         },
     );
 
@@ -463,9 +548,8 @@ fn test_oracles() {
     );
 
     // attempt to add an oracle that does not implement the interface
-    let (oracle_aggregator_id_2, _) =
-        create_oracle_aggregator(&e, &admin, &base, &decimals, &max_age);
-    let result_bad_interface = oracle_aggregator_client.try_add_oracle(&oracle_aggregator_id_2);
+    // - the oracle aggregator implements the SEP-40 interface, so use an address without a contract
+    let result_bad_interface = oracle_aggregator_client.try_add_oracle(&Address::generate(&e));
     assert!(result_bad_interface.is_err());
 }
 
@@ -653,6 +737,26 @@ fn test_base_assets() {
             asset: Asset::Stellar(asset_1.clone()),
             oracle_index: 0,
             max_dev: 0,
+            max_dev_bps: 0,
+            max_dev_up_bps: 0,
+            max_dev_down_bps: 0,
+            sources: vec![&e],
+            weight: 1,
+            aggregation: Aggregation::Median,
+            reference: Reference::None,
+            min_sources: 1,
+            max_mads: 0,
+            dev_rounds: 4,
+            dev_mode: DeviationMode::Previous,
+            round_tolerance: None,
+            max_step_bps: 0,
+            step_window: 0,
+            max_roc_bps: 0,
+            roc_window: 0,
+            bounds: PriceBounds::None,
+            max_age: None,
+            circuit_breaker: false,
+            grace_period: 0,
         },
     );
     e.as_contract(&oracle_aggregator_id, || {
@@ -699,6 +803,26 @@ fn test_base_assets() {
             asset: Asset::Other(symbol_2.clone()),
             oracle_index: 1,
             max_dev: 20,
+            max_dev_bps: 0,
+            max_dev_up_bps: 0,
+            max_dev_down_bps: 0,
+            sources: vec![&e],
+            weight: 1,
+            aggregation: Aggregation::Median,
+            reference: Reference::None,
+            min_sources: 1,
+            max_mads: 0,
+            dev_rounds: 4,
+            dev_mode: DeviationMode::Previous,
+            round_tolerance: None,
+            max_step_bps: 0,
+            step_window: 0,
+            max_roc_bps: 0,
+            roc_window: 0,
+            bounds: PriceBounds::None,
+            max_age: None,
+            circuit_breaker: false,
+            grace_period: 0,
         },
     );
     e.as_contract(&oracle_aggregator_id, || {
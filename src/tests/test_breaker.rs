@@ -0,0 +1,335 @@
+#![cfg(test)]
+
+use crate::errors::OracleAggregatorErrors;
+use crate::testutils::{create_mock_oracle, create_oracle_aggregator, EnvTestUtils};
+use crate::types::Asset;
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events},
+    vec, Address, Env, Error, IntoVal, Symbol,
+};
+
+#[test]
+fn test_circuit_breaker() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let guardian = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_0000000], &(norm_timestamp - 300));
+    oracle.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &5);
+    assert_eq!(aggregator_client.guardian(), admin);
+
+    aggregator_client.set_circuit_breaker(&asset, &true);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_circuit_breaker"),
+                    vec![&e, asset.into_val(&e), true.into_val(&e)]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert!(config.circuit_breaker);
+
+    // breaker is not tripped by a valid price
+    assert!(!aggregator_client.check_breaker(&asset));
+    assert!(aggregator_client.breaker(&asset).is_none());
+
+    // breaker is not tripped by a stale price
+    e.jump_time(901);
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(result.err(), Some(Ok(OracleAggregatorErrors::NoValidPrice)));
+    assert!(!aggregator_client.check_breaker(&asset));
+
+    // breaker is tripped by a price that fails the max_dev check
+    oracle.set_price(&vec![&e, 1_0000000], &(norm_timestamp + 600));
+    oracle.set_price(&vec![&e, 1_1000000], &(norm_timestamp + 900));
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::MaxDevExceeded))
+    );
+    // -> the failed call is reverted, so the breaker is not tripped
+    assert!(aggregator_client.breaker(&asset).is_none());
+    assert!(aggregator_client.check_breaker(&asset));
+    assert_eq!(e.auths().len(), 0);
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                aggregator_id.clone(),
+                (Symbol::new(&e, "breaker_tripped"), asset.clone()).into_val(&e),
+                128u32.into_val(&e)
+            )
+        ]
+    );
+    let breaker = aggregator_client.breaker(&asset).unwrap();
+    assert_eq!(breaker.tripped_at, e.ledger().timestamp());
+    assert_eq!(breaker.reason, 128);
+
+    // breaker stays tripped for the next valid round
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_1000000], &(norm_timestamp + 1200));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::BreakerTripped))
+    );
+    let result = aggregator_client.try_poke(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::BreakerTripped))
+    );
+    assert!(aggregator_client.check_breaker(&asset));
+    let breaker = aggregator_client.breaker(&asset).unwrap();
+    assert_eq!(breaker.tripped_at, e.ledger().timestamp() - 300);
+
+    // no historical price is reported while the breaker is tripped
+    let price = aggregator_client.price(&asset, &(norm_timestamp + 1200));
+    assert!(price.is_none());
+    let prices = aggregator_client.prices(&asset, &2);
+    assert!(prices.is_none());
+    let twap = aggregator_client.twap(&asset, &2);
+    assert!(twap.is_none());
+    let price = aggregator_client.x_price(&asset, &base, &(norm_timestamp + 1200));
+    assert!(price.is_none());
+    let prices = aggregator_client.x_prices(&asset, &base, &2);
+    assert!(prices.is_none());
+    let twap = aggregator_client.x_twap(&asset, &base, &2);
+    assert!(twap.is_none());
+
+    // breaker stays tripped if it is disabled
+    aggregator_client.set_circuit_breaker(&asset, &false);
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    assert!(aggregator_client.check_breaker(&asset));
+
+    // guardian resets the breaker
+    aggregator_client.set_guardian(&guardian);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_guardian"),
+                    vec![&e, guardian.to_val()]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    assert_eq!(aggregator_client.guardian(), guardian);
+
+    aggregator_client.reset_breaker(&asset);
+    assert_eq!(
+        e.auths()[0],
+        (
+            guardian.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "reset_breaker"),
+                    vec![&e, asset.into_val(&e)]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                aggregator_id.clone(),
+                (Symbol::new(&e, "breaker_reset"), asset.clone()).into_val(&e),
+                guardian.into_val(&e)
+            )
+        ]
+    );
+    assert!(aggregator_client.breaker(&asset).is_none());
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_1000000);
+    assert_eq!(price.timestamp, norm_timestamp + 1200);
+    let price = aggregator_client
+        .price(&asset, &(norm_timestamp + 1200))
+        .unwrap();
+    assert_eq!(price.price, 1_1000000);
+    let twap = aggregator_client.twap(&asset, &2).unwrap();
+    assert_eq!(twap, 1_1000000);
+
+    // breaker is tripped by lastprice
+    aggregator_client.set_circuit_breaker(&asset, &true);
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_2000000], &(norm_timestamp + 1500));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                aggregator_id.clone(),
+                (Symbol::new(&e, "breaker_tripped"), asset.clone()).into_val(&e),
+                128u32.into_val(&e)
+            )
+        ]
+    );
+    let breaker = aggregator_client.breaker(&asset).unwrap();
+    assert_eq!(breaker.tripped_at, e.ledger().timestamp());
+    assert_eq!(breaker.reason, 128);
+    aggregator_client.reset_breaker(&asset);
+
+    // breaker is not tripped while disabled
+    aggregator_client.set_circuit_breaker(&asset, &false);
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_3200000], &(norm_timestamp + 1800));
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::MaxDevExceeded))
+    );
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    assert!(!aggregator_client.check_breaker(&asset));
+
+    // breaker cannot be reset if it is not tripped
+    let result = aggregator_client.try_reset_breaker(&asset);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(131))));
+
+    // base assets do not have a breaker
+    assert!(!aggregator_client.check_breaker(&base));
+
+    // asset does not exist
+    let result = aggregator_client.try_check_breaker(&Asset::Other(Symbol::new(&e, "NONE")));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+    let result =
+        aggregator_client.try_set_circuit_breaker(&Asset::Other(Symbol::new(&e, "NONE")), &true);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+}
+
+#[test]
+fn test_circuit_breaker_quorum() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset_0 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_1 = Asset::Other(Symbol::new(&e, "ASSET"));
+    let oracle_asset_2 = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset_0.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset_1.clone()], 7, 300);
+    let oracle_2 = create_mock_oracle(&e, &vec![&e, oracle_asset_2.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_0100000], &norm_timestamp);
+    oracle_2.set_price(&vec![&e, 0_9900000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+    aggregator_client.add_oracle(&oracle_2.address);
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset_0, &0);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset_1);
+    aggregator_client.add_asset_source(&asset, &oracle_2.address, &oracle_asset_2);
+    aggregator_client.set_min_sources(&asset, &3);
+    aggregator_client.set_outlier_filter(&asset, &3);
+    aggregator_client.set_circuit_breaker(&asset, &true);
+
+    assert!(!aggregator_client.check_breaker(&asset));
+
+    // breaker is not tripped when a source goes stale and quorum is not met
+    e.jump_time(901);
+    oracle_0.set_price(&vec![&e, 1_0000000], &(norm_timestamp + 900));
+    oracle_1.set_price(&vec![&e, 1_0100000], &(norm_timestamp + 900));
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(result.err(), Some(Ok(OracleAggregatorErrors::QuorumNotMet)));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    assert!(!aggregator_client.check_breaker(&asset));
+    assert!(aggregator_client.breaker(&asset).is_none());
+
+    // breaker is tripped when the sources disagree and quorum is not met
+    oracle_2.set_price(&vec![&e, 1_5000000], &(norm_timestamp + 900));
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::TooManyOutliers))
+    );
+    assert!(aggregator_client.check_breaker(&asset));
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                aggregator_id.clone(),
+                (Symbol::new(&e, "breaker_tripped"), asset.clone()).into_val(&e),
+                133u32.into_val(&e)
+            )
+        ]
+    );
+    let breaker = aggregator_client.breaker(&asset).unwrap();
+    assert_eq!(breaker.reason, 133);
+
+    // quorum is met again, but the breaker stays tripped
+    oracle_2.set_price(&vec![&e, 0_9900000], &(norm_timestamp + 900));
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::BreakerTripped))
+    );
+
+    // guardian defaults to the admin
+    aggregator_client.reset_breaker(&asset);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "reset_breaker"),
+                    vec![&e, asset.into_val(&e)]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.price, 1_0000000);
+    assert_eq!(price.sources, vec![&e, 0, 1, 2]);
+}
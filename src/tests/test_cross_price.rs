@@ -0,0 +1,160 @@
+#![cfg(test)]
+
+use crate::testutils::{setup_default_aggregator, EnvTestUtils};
+use crate::types::Asset;
+use soroban_sdk::{testutils::Address as _, Address, Env, Symbol, Vec};
+
+#[test]
+fn test_x_last_price() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset_0 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_1 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_2 = Asset::Other(Symbol::new(&e, "wETH"));
+    let asset_0 = Asset::Stellar(Address::generate(&e));
+    let asset_1 = Asset::Stellar(Address::generate(&e));
+    let asset_2 = Asset::Stellar(Address::generate(&e));
+    let asset_3 = Asset::Stellar(Address::generate(&e));
+
+    let (oracle_aggregator_client, oracle_1, oracle_2) = setup_default_aggregator(
+        &e,
+        &admin,
+        &base,
+        &oracle_asset_0,
+        &oracle_asset_1,
+        &oracle_asset_2,
+    );
+
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_110000000, 1_200000000]),
+        &e.ledger().timestamp(),
+    );
+    oracle_2.set_price(
+        &Vec::from_array(&e, [1010_000000]),
+        &(e.ledger().timestamp() - 600),
+    );
+
+    oracle_aggregator_client.add_asset(&asset_0, &oracle_1.address, &oracle_asset_0, &0);
+    oracle_aggregator_client.add_asset(&asset_1, &oracle_1.address, &oracle_asset_1, &0);
+    oracle_aggregator_client.add_asset(&asset_2, &oracle_2.address, &oracle_asset_2, &0);
+    oracle_aggregator_client.add_base_asset(&asset_3);
+
+    // reports the older timestamp of the two prices
+    let price = oracle_aggregator_client
+        .x_last_price(&asset_2, &asset_0)
+        .unwrap();
+    assert_eq!(price.price, 9181_8181818);
+    assert_eq!(price.timestamp, e.ledger().timestamp() - 600);
+
+    let price = oracle_aggregator_client
+        .x_last_price(&asset_0, &asset_1)
+        .unwrap();
+    assert_eq!(price.price, 0_0916666);
+    assert_eq!(price.timestamp, e.ledger().timestamp());
+
+    // base and base-like assets are priced at 1
+    let price = oracle_aggregator_client
+        .x_last_price(&asset_1, &asset_3)
+        .unwrap();
+    assert_eq!(price.price, 1_2000000);
+    assert_eq!(price.timestamp, e.ledger().timestamp());
+
+    let price = oracle_aggregator_client
+        .x_last_price(&base, &asset_0)
+        .unwrap();
+    assert_eq!(price.price, 9_0909090);
+    assert_eq!(price.timestamp, e.ledger().timestamp());
+
+    // jump 301 seconds so the asset_2 price is older than max_age
+    e.jump_time(301);
+
+    let price = oracle_aggregator_client.x_last_price(&asset_2, &asset_0);
+    assert!(price.is_none());
+    let price = oracle_aggregator_client.x_last_price(&asset_0, &asset_2);
+    assert!(price.is_none());
+}
+
+#[test]
+fn test_x_price_and_x_prices() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset_0 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_1 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_2 = Asset::Other(Symbol::new(&e, "wETH"));
+    let asset_0 = Asset::Stellar(Address::generate(&e));
+    let asset_1 = Asset::Stellar(Address::generate(&e));
+    let asset_3 = Asset::Stellar(Address::generate(&e));
+
+    let (oracle_aggregator_client, oracle_1, _) = setup_default_aggregator(
+        &e,
+        &admin,
+        &base,
+        &oracle_asset_0,
+        &oracle_asset_1,
+        &oracle_asset_2,
+    );
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_300_000_000, 1_200_000_000]),
+        &(norm_timestamp - 300),
+    );
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_600_000_000, 1_500_000_000]),
+        &norm_timestamp,
+    );
+
+    oracle_aggregator_client.add_asset(&asset_0, &oracle_1.address, &oracle_asset_0, &0);
+    oracle_aggregator_client.add_asset(&asset_1, &oracle_1.address, &oracle_asset_1, &0);
+    oracle_aggregator_client.add_base_asset(&asset_3);
+
+    let price = oracle_aggregator_client
+        .x_price(&asset_0, &asset_1, &(norm_timestamp - 300))
+        .unwrap();
+    assert_eq!(price.price, 0_2500000);
+    assert_eq!(price.timestamp, norm_timestamp - 300);
+
+    let price = oracle_aggregator_client
+        .x_price(&asset_3, &asset_1, &norm_timestamp)
+        .unwrap();
+    assert_eq!(price.price, 0_6666666);
+    assert_eq!(price.timestamp, norm_timestamp);
+
+    let price = oracle_aggregator_client.x_price(&asset_0, &asset_1, &(norm_timestamp + 300));
+    assert!(price.is_none());
+
+    let prices = oracle_aggregator_client
+        .x_prices(&asset_0, &asset_1, &2)
+        .unwrap();
+    assert_eq!(prices.len(), 2);
+    assert_eq!(prices.get_unchecked(0).price, 0_4000000);
+    assert_eq!(prices.get_unchecked(0).timestamp, norm_timestamp);
+    assert_eq!(prices.get_unchecked(1).price, 0_2500000);
+    assert_eq!(prices.get_unchecked(1).timestamp, norm_timestamp - 300);
+
+    // base-like base asset reports the inverse of each quote round
+    let prices = oracle_aggregator_client
+        .x_prices(&asset_3, &asset_0, &2)
+        .unwrap();
+    assert_eq!(prices.len(), 2);
+    assert_eq!(prices.get_unchecked(0).price, 1_6666666);
+    assert_eq!(prices.get_unchecked(0).timestamp, norm_timestamp);
+    assert_eq!(prices.get_unchecked(1).price, 3_3333333);
+    assert_eq!(prices.get_unchecked(1).timestamp, norm_timestamp - 300);
+
+    // base-like quote asset reports the base asset prices
+    let prices = oracle_aggregator_client
+        .x_prices(&asset_1, &asset_3, &2)
+        .unwrap();
+    assert_eq!(prices.len(), 2);
+    assert_eq!(prices.get_unchecked(0).price, 1_5000000);
+    assert_eq!(prices.get_unchecked(1).price, 1_2000000);
+}
@@ -1,9 +1,15 @@
 #![cfg(test)]
 
-use crate::testutils::{create_oracle_aggregator, setup_default_aggregator, EnvTestUtils};
-use crate::types::Asset;
-use soroban_sdk::{testutils::Address as _, vec, Address, Env, Symbol, Vec};
-use soroban_sdk::{IntoVal, Val};
+use crate::errors::OracleAggregatorErrors;
+use crate::testutils::{
+    create_mock_oracle, create_oracle_aggregator, setup_default_aggregator, EnvTestUtils,
+};
+use crate::types::{Asset, BoundsConfig, BoundsPolicy, DeviationMode, PriceBounds};
+use soroban_sdk::IntoVal;
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    vec, Address, Env, Error, Symbol, Vec,
+};
 
 use super::snapshot;
 
@@ -383,6 +389,806 @@ fn test_lastprice_max_dev_too_large_abs() {
     assert!(price_0.is_none());
 }
 
+#[test]
+fn test_lastprice_max_dev_modes() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_0000000], &(norm_timestamp - 900));
+    oracle.set_price(&vec![&e, 1_0000000], &(norm_timestamp - 600));
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &10);
+
+    // a single manipulated prior round
+    oracle.set_price(&vec![&e, 1_5000000], &(norm_timestamp - 300));
+    oracle.set_price(&vec![&e, 1_0200000], &norm_timestamp);
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.dev_rounds, 4);
+    assert_eq!(config.dev_mode, DeviationMode::Previous);
+
+    // latest vs previous round fails
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+
+    // latest vs median of the prior rounds ignores the manipulated round
+    aggregator_client.set_deviation_check(&asset, &4, &DeviationMode::Median);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_deviation_check"),
+                    vec![
+                        &e,
+                        asset.into_val(&e),
+                        4u32.into_val(&e),
+                        DeviationMode::Median.into_val(&e),
+                    ]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.dev_rounds, 4);
+    assert_eq!(config.dev_mode, DeviationMode::Median);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0200000);
+    assert_eq!(price.timestamp, norm_timestamp);
+
+    // latest vs mean of the prior rounds
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_0400000], &(norm_timestamp + 300));
+
+    // -> mean of [1.02, 1.5, 1.0] = 1.1733
+    aggregator_client.set_deviation_check(&asset, &4, &DeviationMode::Mean);
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+
+    // -> mean of [1.02, 1.5, 1.0, 1.0] = 1.13
+    aggregator_client.set_deviation_check(&asset, &5, &DeviationMode::Mean);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0400000);
+    assert_eq!(price.timestamp, norm_timestamp + 300);
+
+    // invalid round counts
+    let result = aggregator_client.try_set_deviation_check(&asset, &1, &DeviationMode::Mean);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(118))));
+    let result = aggregator_client.try_set_deviation_check(&asset, &11, &DeviationMode::Mean);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(118))));
+
+    // asset does not exist
+    let result = aggregator_client.try_set_deviation_check(&base, &4, &DeviationMode::Mean);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+}
+
+#[test]
+fn test_lastprice_max_dev_bps() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_0000000], &(norm_timestamp - 300));
+    oracle.set_price(&vec![&e, 1_0030000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &1);
+
+    // 0.3% is within 1%
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0030000);
+
+    aggregator_client.set_max_dev_bps(&asset, &30);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_max_dev_bps"),
+                    vec![&e, asset.into_val(&e), 30u32.into_val(&e)]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.max_dev, 1);
+    assert_eq!(config.max_dev_bps, 30);
+
+    // a deviation equal to max_dev_bps is rejected
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+
+    // a deviation just below max_dev_bps is accepted
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_0000001], &(norm_timestamp + 300));
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 0_9970002], &(norm_timestamp + 600));
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 0_9970002);
+    assert_eq!(price.timestamp, norm_timestamp + 600);
+
+    // resetting max_dev_bps falls back to max_dev
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_0050000], &(norm_timestamp + 900));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    aggregator_client.set_max_dev_bps(&asset, &0);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0050000);
+
+    // invalid max_dev_bps
+    let result = aggregator_client.try_set_max_dev_bps(&asset, &10000);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(119))));
+    aggregator_client.set_max_dev_bps(&asset, &9999);
+
+    // asset does not exist
+    let result = aggregator_client.try_set_max_dev_bps(&base, &30);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+}
+
+#[test]
+fn test_lastprice_max_dev_limits() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_0000000], &(norm_timestamp - 300));
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &0);
+
+    // collateral asset allows a 5% drop but only a 1% spike
+    aggregator_client.set_max_dev_limits(&asset, &100, &500);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_max_dev_limits"),
+                    vec![
+                        &e,
+                        asset.into_val(&e),
+                        100u32.into_val(&e),
+                        500u32.into_val(&e),
+                    ]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.max_dev_up_bps, 100);
+    assert_eq!(config.max_dev_down_bps, 500);
+
+    // drop of 4%
+    oracle.set_price(&vec![&e, 0_9600000], &norm_timestamp);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 0_9600000);
+    assert_eq!(price.timestamp, norm_timestamp);
+
+    // spike of 2%
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 0_9792000], &(norm_timestamp + 300));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+
+    // spike of 0.59%
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 0_9850000], &(norm_timestamp + 600));
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 0_9850000);
+
+    // drop is not checked without a downward or symmetric limit
+    aggregator_client.set_max_dev_limits(&asset, &100, &0);
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 0_5000000], &(norm_timestamp + 900));
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 0_5000000);
+
+    // drop uses the symmetric limit if one is set
+    aggregator_client.set_max_dev_bps(&asset, &1000);
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+
+    // invalid limits
+    let result = aggregator_client.try_set_max_dev_limits(&asset, &10000, &0);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(119))));
+    let result = aggregator_client.try_set_max_dev_limits(&asset, &0, &10000);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(119))));
+
+    // asset does not exist
+    let result = aggregator_client.try_set_max_dev_limits(&base, &100, &500);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+}
+
+#[test]
+fn test_lastprice_rate_limit() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    // price walks up 4% each round
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_0000000], &(norm_timestamp - 900));
+    oracle.set_price(&vec![&e, 1_0400000], &(norm_timestamp - 600));
+    oracle.set_price(&vec![&e, 1_0816000], &(norm_timestamp - 300));
+    oracle.set_price(&vec![&e, 1_1248640], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &5);
+
+    // each round passes the max_dev check
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_1248640);
+
+    aggregator_client.set_rate_limit(&asset, &1000, &900);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_rate_limit"),
+                    vec![
+                        &e,
+                        asset.into_val(&e),
+                        1000u32.into_val(&e),
+                        900u64.into_val(&e),
+                    ]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.max_roc_bps, 1000);
+    assert_eq!(config.roc_window, 900);
+
+    // moved 12.49% over the window
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+
+    // moved 8.16% over a shorter window
+    aggregator_client.set_rate_limit(&asset, &1000, &600);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_1248640);
+    assert_eq!(price.timestamp, norm_timestamp);
+
+    // price walks back within the window
+    aggregator_client.set_rate_limit(&asset, &1000, &900);
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_1000000], &(norm_timestamp + 300));
+    // -> 1.1 vs [1.1249, 1.0816, 1.04]
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_1000000);
+    assert_eq!(price.timestamp, norm_timestamp + 300);
+
+    aggregator_client.set_rate_limit(&asset, &0, &0);
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.max_roc_bps, 0);
+
+    // invalid rate limits
+    let result = aggregator_client.try_set_rate_limit(&asset, &10000, &900);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(119))));
+    let result = aggregator_client.try_set_rate_limit(&asset, &1000, &0);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(120))));
+
+    // window needs more than 30 rounds of the oracle
+    let result = aggregator_client.try_set_rate_limit(&asset, &2000, &86400);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(120))));
+    let result = aggregator_client.try_set_rate_limit(&asset, &2000, &9000);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(120))));
+    aggregator_client.set_rate_limit(&asset, &2000, &8700);
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.roc_window, 8700);
+
+    // a source that cannot cover the window cannot be added
+    let oracle_asset_2 = Asset::Stellar(Address::generate(&e));
+    let oracle_2 = create_mock_oracle(&e, &vec![&e, oracle_asset_2.clone()], 7, 60);
+    oracle_2.set_price(&vec![&e, 1_1000000], &(e.ledger().timestamp() / 60 * 60));
+    aggregator_client.add_oracle(&oracle_2.address);
+    let result = aggregator_client.try_add_asset_source(&asset, &oracle_2.address, &oracle_asset_2);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(120))));
+
+    // asset does not exist
+    let result = aggregator_client.try_set_rate_limit(&base, &1000, &900);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+}
+
+#[test]
+fn test_lastprice_price_bounds() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "USD"));
+
+    let oracle_asset = Asset::Other(Symbol::new(&e, "EURC"));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 14, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_08000000000000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &0);
+
+    let bounds = PriceBounds::Bounded(BoundsConfig {
+        min_price: 1_0000000,
+        max_price: 1_2000000,
+        policy: BoundsPolicy::Reject,
+    });
+    aggregator_client.set_price_bounds(&asset, &bounds);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_price_bounds"),
+                    vec![&e, asset.into_val(&e), bounds.into_val(&e)]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    match config.bounds {
+        PriceBounds::Bounded(bounds) => {
+            assert_eq!(bounds.min_price, 1_0000000);
+            assert_eq!(bounds.max_price, 1_2000000);
+            assert_eq!(bounds.policy, BoundsPolicy::Reject);
+        }
+        PriceBounds::None => panic!("Price bounds not set"),
+    };
+
+    // price is within bounds
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0800000);
+    assert_eq!(price.timestamp, norm_timestamp);
+
+    // price is below the floor
+    let mut bounds_config = BoundsConfig {
+        min_price: 1_1000000,
+        max_price: 1_2000000,
+        policy: BoundsPolicy::Reject,
+    };
+    aggregator_client.set_price_bounds(&asset, &PriceBounds::Bounded(bounds_config.clone()));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::PriceOutOfBounds))
+    );
+
+    // price is clamped to the floor
+    bounds_config.policy = BoundsPolicy::Clamp;
+    aggregator_client.set_price_bounds(&asset, &PriceBounds::Bounded(bounds_config.clone()));
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_1000000);
+    assert_eq!(price.timestamp, norm_timestamp);
+
+    // price is clamped to the ceiling
+    bounds_config.min_price = 0;
+    bounds_config.max_price = 1_0500000;
+    aggregator_client.set_price_bounds(&asset, &PriceBounds::Bounded(bounds_config.clone()));
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0500000);
+
+    // bounds can be removed
+    aggregator_client.set_price_bounds(&asset, &PriceBounds::None);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0800000);
+
+    // invalid bounds
+    bounds_config.min_price = -1;
+    let result = aggregator_client
+        .try_set_price_bounds(&asset, &PriceBounds::Bounded(bounds_config.clone()));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(122))));
+    bounds_config.min_price = 1_0500000;
+    let result = aggregator_client
+        .try_set_price_bounds(&asset, &PriceBounds::Bounded(bounds_config.clone()));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(122))));
+
+    // asset does not exist
+    let result = aggregator_client.try_set_price_bounds(&base, &PriceBounds::None);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+}
+
+#[test]
+fn test_lastprice_source_sanity() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_0000000], &(norm_timestamp - 300));
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &0);
+
+    // zero price
+    oracle.set_price(&vec![&e, 0], &norm_timestamp);
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::NonPositivePrice))
+    );
+
+    // negative price
+    oracle.set_price(&vec![&e, -1_0000000], &norm_timestamp);
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::NonPositivePrice))
+    );
+
+    // timestamp is more than 60s past the ledger timestamp
+    e.jump_time(239);
+    oracle.set_price(&vec![&e, 1_0000000], &(norm_timestamp + 300));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::FutureTimestamp))
+    );
+
+    // timestamp within 60s of the ledger timestamp is allowed
+    e.jump_time(1);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0000000);
+    assert_eq!(price.timestamp, norm_timestamp + 300);
+
+    // mock oracle rounds timestamps to the resolution, so misaligned timestamps are
+    // tested in the price_data unit tests
+    e.jump_time(100);
+
+    // sanity checks apply to the max_dev check
+    aggregator_client.set_max_dev_bps(&asset, &500);
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, -1_0000000], &(norm_timestamp + 600));
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::NonPositivePrice))
+    );
+}
+
+#[test]
+fn test_lastprice_asset_max_age() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    // oracle updates hourly
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 3600);
+
+    let norm_timestamp = e.ledger().timestamp() / 3600 * 3600;
+    oracle.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &0);
+
+    // price is older than the global max age
+    e.jump_time(1000);
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+
+    aggregator_client.set_asset_max_age(&asset, &Some(3900));
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_asset_max_age"),
+                    vec![&e, asset.into_val(&e), Some(3900u64).into_val(&e)]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.max_age, Some(3900));
+
+    // price is within the asset max age
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0000000);
+    assert_eq!(price.timestamp, norm_timestamp);
+
+    e.jump_time(2900);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0000000);
+    e.jump_time(1);
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+
+    // asset max age can be tighter than the global max age
+    e.jump_time(3599);
+    oracle.set_price(&vec![&e, 1_0000000], &(norm_timestamp + 7200));
+    e.jump_time(300);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.timestamp, norm_timestamp + 7200);
+    aggregator_client.set_asset_max_age(&asset, &Some(299));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+
+    // reset to the global max age
+    aggregator_client.set_asset_max_age(&asset, &None);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.timestamp, norm_timestamp + 7200);
+
+    // invalid max age
+    let result = aggregator_client.try_set_asset_max_age(&asset, &Some(59));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(105))));
+    let result = aggregator_client.try_set_asset_max_age(&asset, &Some(86401));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(105))));
+
+    // asset does not exist
+    let result = aggregator_client.try_set_asset_max_age(&base, &Some(3900));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+}
+
+#[test]
+fn test_lastprice_oracle_liveness() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &0);
+    assert_eq!(
+        aggregator_client.oracles().get_unchecked(0).liveness_rounds,
+        0
+    );
+
+    // oracle missed 2 rounds, but the price is within max_age
+    e.jump_time(601);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0000000);
+
+    aggregator_client.set_oracle_liveness(&oracle.address, &2);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_oracle_liveness"),
+                    vec![&e, oracle.address.to_val(), 2u32.into_val(&e)]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let oracle_config = aggregator_client.oracles().get_unchecked(0);
+    assert_eq!(oracle_config.liveness_rounds, 2);
+    assert_eq!(oracle_config.resolution, 300);
+
+    // latest round is older than 2 rounds
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::OracleNotLive))
+    );
+
+    // latest round is within 3 rounds
+    aggregator_client.set_oracle_liveness(&oracle.address, &3);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0000000);
+    assert_eq!(price.timestamp, norm_timestamp);
+
+    // oracle does not exist
+    let result = aggregator_client.try_set_oracle_liveness(&Address::generate(&e), &2);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(107))));
+}
+
+#[test]
+fn test_lastprice_max_dev_missing_round() {
+    // mock oracle stops fetching `prices` at the first missing round, so use the snapshot
+    let e = snapshot::env_from_snapshot();
+    e.mock_all_auths();
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let xlm_address = Address::from_str(&e, snapshot::XLM);
+    let xlm_asset = Asset::Stellar(xlm_address.clone());
+
+    let bombadil = Address::generate(&e);
+    let reflector = Address::from_str(&e, snapshot::REFLECTOR);
+
+    let (aggregator_id, aggregator_client) =
+        create_oracle_aggregator(&e, &bombadil, &base, &7, &900);
+
+    aggregator_client.add_oracle(&reflector);
+
+    aggregator_client.add_asset(&xlm_asset, &reflector, &xlm_asset, &10);
+    let config = aggregator_client
+        .asset_configs()
+        .get(xlm_asset.clone())
+        .unwrap();
+    assert_eq!(config.round_tolerance, None);
+
+    let mut round_timestamp = snapshot::LAST_UPDATE_TIMESTAMP;
+    e.jump_time(300);
+    round_timestamp += 300;
+    snapshot::set_reflector_prices(&e, 0_3000000_0000000, 1, 1, 1);
+
+    // source skips a round
+    e.jump_time(600);
+    round_timestamp += 600;
+    snapshot::set_reflector_prices(&e, 0_3060000_0000000, 1, 1, 1);
+
+    // gap between rounds is not checked by default
+    let price = aggregator_client.lastprice(&xlm_asset).unwrap();
+    assert_eq!(price.price, 0_3060000);
+    assert_eq!(price.timestamp, round_timestamp);
+
+    // rounds must be exactly 1 resolution apart
+    aggregator_client.set_round_tolerance(&xlm_asset, &Some(0));
+    assert_eq!(
+        e.auths()[0],
+        (
+            bombadil.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_round_tolerance"),
+                    vec![&e, xlm_asset.into_val(&e), Some(0u32).into_val(&e)]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(xlm_asset.clone())
+        .unwrap();
+    assert_eq!(config.round_tolerance, Some(0));
+    let price = aggregator_client.lastprice(&xlm_asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&xlm_asset);
+    assert_eq!(result.err(), Some(Ok(OracleAggregatorErrors::MissingRound)));
+
+    // a gap equal to the resolution plus the tolerance is allowed
+    aggregator_client.set_round_tolerance(&xlm_asset, &Some(300));
+    let price = aggregator_client.lastprice(&xlm_asset).unwrap();
+    assert_eq!(price.price, 0_3060000);
+
+    // previous mode only checks the latest 2 rounds
+    aggregator_client.set_round_tolerance(&xlm_asset, &Some(0));
+    e.jump_time(300);
+    round_timestamp += 300;
+    snapshot::set_reflector_prices(&e, 0_3090000_0000000, 1, 1, 1);
+    let price = aggregator_client.lastprice(&xlm_asset).unwrap();
+    assert_eq!(price.price, 0_3090000);
+    assert_eq!(price.timestamp, round_timestamp);
+
+    // median mode checks every round fetched
+    aggregator_client.set_deviation_check(&xlm_asset, &4, &DeviationMode::Median);
+    let price = aggregator_client.lastprice(&xlm_asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&xlm_asset);
+    assert_eq!(result.err(), Some(Ok(OracleAggregatorErrors::MissingRound)));
+
+    aggregator_client.set_deviation_check(&xlm_asset, &2, &DeviationMode::Median);
+    let price = aggregator_client.lastprice(&xlm_asset).unwrap();
+    assert_eq!(price.price, 0_3090000);
+
+    // asset does not exist
+    let result = aggregator_client.try_set_round_tolerance(&base, &None);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+}
+
 #[test]
 fn test_lastprice_max_dev_fetches_4_rounds() {
     // mock oracle does not behave like reflector for `prices`
@@ -407,7 +1213,7 @@ fn test_lastprice_max_dev_fetches_4_rounds() {
 
     // set starting prices on the reflector contract
     round_timestamp += 300;
-    set_reflector_prices(&e, 0_3000000_0000000);
+    snapshot::set_reflector_prices(&e, 0_3000000_0000000, 1, 1, 1);
 
     // TEST: Verify lastprice can verify last price if it is 4 rounds ago
 
@@ -415,7 +1221,7 @@ fn test_lastprice_max_dev_fetches_4_rounds() {
     e.jump_time(900);
 
     round_timestamp += 900;
-    set_reflector_prices(&e, 0_3100000_0000000);
+    snapshot::set_reflector_prices(&e, 0_3100000_0000000, 1, 1, 1);
 
     e.jump_time(100);
 
@@ -426,7 +1232,7 @@ fn test_lastprice_max_dev_fetches_4_rounds() {
     // TEST: Verify lastprice returns none if last price is > 4 rounds ago
     e.jump_time(200);
     e.jump_time(900);
-    set_reflector_prices(&e, 0_3200000_0000000);
+    snapshot::set_reflector_prices(&e, 0_3200000_0000000, 1, 1, 1);
 
     let xlm_price = aggregator_client.lastprice(&xlm_asset);
     assert!(xlm_price.is_none());
@@ -483,27 +1289,3 @@ fn test_lastprice_max_dev_first_price_too_old() {
     let price_0 = oracle_aggregator_client.lastprice(&asset_0);
     assert!(price_0.is_none());
 }
-
-/// Set prices on the reflector contract for the most recent round based on the
-/// current ledger timestamp.
-fn set_reflector_prices(e: &Env, xlm_price: i128) {
-    let timestamp: u64 = (e.ledger().timestamp() / 300u64) * 300u64 * 1000u64;
-    let reflector = Address::from_str(&e, snapshot::REFLECTOR);
-    let price_array: Vec<i128> = vec![
-        e, 1, // BTCLN
-        1, // AQUA,
-        1, // yUSDC
-        1, // FIDR
-        1, // SSLX
-        1, // ARST
-        1, // mykobo EURC
-        xlm_price, 1, // XRP
-        1, // EURC
-        1, // XRF
-        1, // USDGLO
-        1, // CETES
-        1, // USTRY
-    ];
-    let args: Vec<Val> = vec![e, price_array.into_val(e), timestamp.into_val(e)];
-    e.invoke_contract::<Val>(&reflector, &Symbol::new(e, "set_price"), args);
-}
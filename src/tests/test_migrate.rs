@@ -0,0 +1,163 @@
+#![cfg(test)]
+
+use crate::storage;
+use crate::testutils::{
+    assert_asset_config_equal, create_mock_oracle, create_oracle_aggregator, EnvTestUtils,
+};
+use crate::types::{
+    Aggregation, Asset, AssetConfig, DeviationMode, LegacyAssetConfig, LegacyOracleConfig,
+    PriceBounds, Reference,
+};
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    vec, Address, Env, Error, Map, Symbol,
+};
+
+#[test]
+fn test_migrate() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset_0 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_1 = Asset::Other(Symbol::new(&e, "wETH"));
+    let asset_0 = Asset::Stellar(Address::generate(&e));
+    let asset_1 = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(
+        &e,
+        &vec![&e, oracle_asset_0.clone(), oracle_asset_1.clone()],
+        7,
+        300,
+    );
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_0000000, 2000_0000000], &(norm_timestamp - 300));
+    oracle.set_price(&vec![&e, 1_0100000, 2010_0000000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+
+    // storage is already in the current layout
+    let result = aggregator_client.try_migrate();
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(134))));
+
+    // write oracle and asset configs in the original layout
+    e.as_contract(&aggregator_id, || {
+        let legacy_oracles = vec![
+            &e,
+            LegacyOracleConfig {
+                address: oracle.address.clone(),
+                index: 0,
+                resolution: 300,
+                decimals: 7,
+            },
+        ];
+        e.storage()
+            .instance()
+            .set(&Symbol::new(&e, "Oracles"), &legacy_oracles);
+        let mut legacy_configs: Map<Asset, LegacyAssetConfig> = Map::new(&e);
+        legacy_configs.set(
+            asset_0.clone(),
+            LegacyAssetConfig {
+                asset: oracle_asset_0.clone(),
+                oracle_index: 0,
+                max_dev: 5,
+            },
+        );
+        legacy_configs.set(
+            asset_1.clone(),
+            LegacyAssetConfig {
+                asset: oracle_asset_1.clone(),
+                oracle_index: 0,
+                max_dev: 0,
+            },
+        );
+        e.storage()
+            .instance()
+            .set(&Symbol::new(&e, "Assets"), &legacy_configs);
+        e.storage().instance().remove(&Symbol::new(&e, "Version"));
+        assert_eq!(storage::get_version(&e), 1);
+    });
+
+    // the original layout cannot be read until it is migrated
+    let result = aggregator_client.try_lastprice(&asset_0);
+    assert!(result.is_err());
+
+    aggregator_client.migrate();
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "migrate"),
+                    vec![&e]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    e.as_contract(&aggregator_id, || {
+        assert_eq!(storage::get_version(&e), storage::STORAGE_VERSION);
+    });
+
+    let oracles = aggregator_client.oracles();
+    assert_eq!(oracles.len(), 1);
+    let oracle_config = oracles.get_unchecked(0);
+    assert_eq!(oracle_config.address, oracle.address);
+    assert_eq!(oracle_config.index, 0);
+    assert_eq!(oracle_config.resolution, 300);
+    assert_eq!(oracle_config.decimals, 7);
+    assert_eq!(oracle_config.liveness_rounds, 0);
+
+    let configs = aggregator_client.asset_configs();
+    assert_eq!(configs.len(), 2);
+    let expected_config = AssetConfig {
+        asset: oracle_asset_0.clone(),
+        oracle_index: 0,
+        max_dev: 5,
+        max_dev_bps: 0,
+        max_dev_up_bps: 0,
+        max_dev_down_bps: 0,
+        sources: vec![&e],
+        weight: 1,
+        aggregation: Aggregation::Median,
+        reference: Reference::None,
+        min_sources: 1,
+        max_mads: 0,
+        dev_rounds: 4,
+        dev_mode: DeviationMode::Previous,
+        round_tolerance: None,
+        max_step_bps: 0,
+        step_window: 0,
+        max_roc_bps: 0,
+        roc_window: 0,
+        bounds: PriceBounds::None,
+        max_age: None,
+        circuit_breaker: false,
+        grace_period: 0,
+    };
+    assert_asset_config_equal(
+        configs.get(asset_0.clone()).unwrap(),
+        expected_config.clone(),
+    );
+    let mut expected_config = expected_config;
+    expected_config.asset = oracle_asset_1.clone();
+    expected_config.max_dev = 0;
+    assert_asset_config_equal(configs.get(asset_1.clone()).unwrap(), expected_config);
+
+    // migrated assets report prices with their original max_dev
+    let price = aggregator_client.lastprice(&asset_0).unwrap();
+    assert_eq!(price.price, 1_0100000);
+    assert_eq!(price.timestamp, norm_timestamp);
+    let price = aggregator_client.lastprice(&asset_1).unwrap();
+    assert_eq!(price.price, 2010_0000000);
+
+    // storage can only be migrated once
+    let result = aggregator_client.try_migrate();
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(134))));
+}
@@ -0,0 +1,447 @@
+#![cfg(test)]
+
+use crate::errors::OracleAggregatorErrors;
+use crate::testutils::{create_mock_oracle, create_oracle_aggregator, EnvTestUtils};
+use crate::types::Asset;
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    vec, Address, Env, Error, IntoVal, Symbol,
+};
+
+#[test]
+fn test_poke() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &0);
+
+    assert!(aggregator_client.last_accepted_price(&asset).is_none());
+
+    // poke stores the last price
+    let price = aggregator_client.poke(&asset);
+    assert_eq!(price.price, 1_0000000);
+    assert_eq!(price.timestamp, norm_timestamp);
+    assert_eq!(e.auths().len(), 0);
+    let last_price = aggregator_client.last_accepted_price(&asset).unwrap();
+    assert_eq!(last_price.price, 1_0000000);
+    assert_eq!(last_price.timestamp, norm_timestamp);
+
+    // poke updates the last accepted price once a new round is reported
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_1000000], &(norm_timestamp + 300));
+    let price = aggregator_client.poke(&asset);
+    assert_eq!(price.price, 1_1000000);
+    let last_price = aggregator_client.last_accepted_price(&asset).unwrap();
+    assert_eq!(last_price.price, 1_1000000);
+    assert_eq!(last_price.timestamp, norm_timestamp + 300);
+
+    // base assets do not store a last accepted price
+    let price = aggregator_client.poke(&base);
+    assert_eq!(price.price, 1_0000000);
+    assert!(aggregator_client.last_accepted_price(&base).is_none());
+
+    // poke fails if the price cannot be resolved
+    e.jump_time(901);
+    let result = aggregator_client.try_poke(&asset);
+    assert_eq!(result.err(), Some(Ok(OracleAggregatorErrors::NoValidPrice)));
+    let last_price = aggregator_client.last_accepted_price(&asset).unwrap();
+    assert_eq!(last_price.price, 1_1000000);
+}
+
+#[test]
+fn test_lastprice_max_step() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &0);
+
+    aggregator_client.set_max_step(&asset, &200, &900);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_max_step"),
+                    vec![
+                        &e,
+                        asset.into_val(&e),
+                        200u32.into_val(&e),
+                        900u64.into_val(&e),
+                    ]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.max_step_bps, 200);
+    assert_eq!(config.step_window, 900);
+
+    // no last accepted price exists
+    aggregator_client.poke(&asset);
+
+    let anchor = aggregator_client.step_anchor(&asset).unwrap();
+    assert_eq!(anchor.price, 1_0000000);
+    assert_eq!(anchor.timestamp, norm_timestamp);
+
+    // step of 1.9%, poked within the step window
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_0190000], &(norm_timestamp + 300));
+    let price = aggregator_client.poke(&asset);
+    assert_eq!(price.price, 1_0190000);
+    let last_price = aggregator_client.last_accepted_price(&asset).unwrap();
+    assert_eq!(last_price.price, 1_0190000);
+    assert_eq!(last_price.timestamp, norm_timestamp + 300);
+    // -> the step anchor does not move within the step window
+    let anchor = aggregator_client.step_anchor(&asset).unwrap();
+    assert_eq!(anchor.price, 1_0000000);
+    assert_eq!(anchor.timestamp, norm_timestamp);
+
+    // each round moves less than 2%, but the price walked 3.8% from the step anchor
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_0380000], &(norm_timestamp + 600));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::MaxStepExceeded))
+    );
+    let result = aggregator_client.try_poke(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::MaxStepExceeded))
+    );
+    let last_price = aggregator_client.last_accepted_price(&asset).unwrap();
+    assert_eq!(last_price.price, 1_0190000);
+    assert_eq!(last_price.timestamp, norm_timestamp + 300);
+
+    // price at the end of the step window is still checked
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_0380000], &(norm_timestamp + 900));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+
+    // price after the step window is not checked
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_0380000], &(norm_timestamp + 1200));
+    let price = aggregator_client.poke(&asset);
+    assert_eq!(price.price, 1_0380000);
+    let last_price = aggregator_client.last_accepted_price(&asset).unwrap();
+    assert_eq!(last_price.price, 1_0380000);
+    assert_eq!(last_price.timestamp, norm_timestamp + 1200);
+    let anchor = aggregator_client.step_anchor(&asset).unwrap();
+    assert_eq!(anchor.price, 1_0380000);
+    assert_eq!(anchor.timestamp, norm_timestamp + 1200);
+
+    // check can be disabled
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_5000000], &(norm_timestamp + 1500));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    aggregator_client.set_max_step(&asset, &0, &0);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_5000000);
+}
+
+#[test]
+fn test_set_max_step_errors() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+
+    // asset does not exist
+    let result = aggregator_client.try_set_max_step(&asset, &200, &900);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &0);
+
+    // invalid max step
+    let result = aggregator_client.try_set_max_step(&asset, &10000, &900);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(119))));
+
+    // invalid step window
+    let result = aggregator_client.try_set_max_step(&asset, &200, &0);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(120))));
+
+    aggregator_client.set_max_step(&asset, &9999, &1);
+}
+
+#[test]
+fn test_lastprice_fallback() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &0);
+
+    aggregator_client.set_grace_period(&asset, &600);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_grace_period"),
+                    vec![&e, asset.into_val(&e), 600u64.into_val(&e)]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.grace_period, 600);
+
+    // no fallback price exists until the asset is poked
+    e.jump_time(901);
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(result.err(), Some(Ok(OracleAggregatorErrors::NoValidPrice)));
+
+    e.jump_time(299);
+    oracle.set_price(&vec![&e, 1_0100000], &(norm_timestamp + 1200));
+    let price = aggregator_client.poke(&asset);
+    assert_eq!(price.price, 1_0100000);
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.price, 1_0100000);
+    assert_eq!(price.sources, vec![&e, 0]);
+    assert!(!price.fallback);
+
+    // the last accepted price is reported once the source is stale
+    e.jump_time(901);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0100000);
+    assert_eq!(price.timestamp, norm_timestamp + 1200);
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.price, 1_0100000);
+    assert_eq!(price.timestamp, norm_timestamp + 1200);
+    assert_eq!(price.sources.len(), 0);
+    assert!(price.fallback);
+    let confidence = aggregator_client.confidence(&asset);
+    assert_eq!(confidence.price, 1_0100000);
+    assert_eq!(confidence.sources, 0);
+    assert!(confidence.fallback);
+
+    // the last accepted price is reported until the grace period passes
+    e.jump_time(599);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0100000);
+    e.jump_time(1);
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(result.err(), Some(Ok(OracleAggregatorErrors::NoValidPrice)));
+
+    // a price that fails a check does not fall back to the last accepted price
+    let timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 1_0200000], &timestamp);
+    let price = aggregator_client.poke(&asset);
+    assert_eq!(price.price, 1_0200000);
+    aggregator_client.set_max_dev_bps(&asset, &100);
+    e.jump_time(300);
+    oracle.set_price(&vec![&e, 1_1000000], &(timestamp + 300));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::MaxDevExceeded))
+    );
+
+    // no fallback price is reported without a grace period
+    aggregator_client.set_grace_period(&asset, &0);
+    e.jump_time(901);
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+
+    // invalid grace period
+    let result = aggregator_client.try_set_grace_period(&asset, &86401);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(120))));
+
+    // asset does not exist
+    let result =
+        aggregator_client.try_set_grace_period(&Asset::Other(Symbol::new(&e, "NONE")), &600);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+}
+
+#[test]
+fn test_lastprice_fallback_outliers() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset_0 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_1 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_2 = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset_0.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset_1.clone()], 7, 300);
+    let oracle_2 = create_mock_oracle(&e, &vec![&e, oracle_asset_2.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_0100000], &norm_timestamp);
+    oracle_2.set_price(&vec![&e, 0_9900000], &norm_timestamp);
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+    aggregator_client.add_oracle(&oracle_2.address);
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset_0, &0);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset_1);
+    aggregator_client.add_asset_source(&asset, &oracle_2.address, &oracle_asset_2);
+    aggregator_client.set_min_sources(&asset, &3);
+    aggregator_client.set_outlier_filter(&asset, &3);
+    aggregator_client.set_grace_period(&asset, &600);
+
+    let price = aggregator_client.poke(&asset);
+    assert_eq!(price.price, 1_0000000);
+
+    // a dropped outlier breaks the quorum, but does not fall back to the last accepted price
+    e.jump_time(300);
+    oracle_0.set_price(&vec![&e, 1_0000000], &(norm_timestamp + 300));
+    oracle_1.set_price(&vec![&e, 1_0100000], &(norm_timestamp + 300));
+    oracle_2.set_price(&vec![&e, 1_5000000], &(norm_timestamp + 300));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::TooManyOutliers))
+    );
+
+    // an unavailable source breaks the quorum, and falls back to the last accepted price
+    e.jump_time(901);
+    oracle_0.set_price(&vec![&e, 1_0000000], &(norm_timestamp + 1200));
+    oracle_1.set_price(&vec![&e, 1_0100000], &(norm_timestamp + 1200));
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0000000);
+    assert_eq!(price.timestamp, norm_timestamp);
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.sources.len(), 0);
+    assert!(price.fallback);
+
+    // without a fallback price, the quorum is not met
+    aggregator_client.set_grace_period(&asset, &0);
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(result.err(), Some(Ok(OracleAggregatorErrors::QuorumNotMet)));
+}
+
+#[test]
+fn test_lastprice_fallback_rejected_source() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset_0 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_1 = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset_0.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset_1.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &(norm_timestamp - 300));
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_0000000], &(norm_timestamp - 300));
+    oracle_1.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset_0, &5);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset_1);
+    aggregator_client.set_min_sources(&asset, &2);
+    aggregator_client.set_grace_period(&asset, &600);
+
+    let price = aggregator_client.poke(&asset);
+    assert_eq!(price.price, 1_0000000);
+
+    // a source that fails the max_dev check breaks the quorum, but does not fall back
+    e.jump_time(300);
+    oracle_0.set_price(&vec![&e, 1_0000000], &(norm_timestamp + 300));
+    oracle_1.set_price(&vec![&e, 1_2000000], &(norm_timestamp + 300));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::MaxDevExceeded))
+    );
+
+    // a source that is unavailable breaks the quorum, and falls back
+    e.jump_time(901);
+    oracle_0.set_price(&vec![&e, 1_0000000], &(norm_timestamp + 900));
+    oracle_0.set_price(&vec![&e, 1_0000000], &(norm_timestamp + 1200));
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    let price = result.unwrap().unwrap();
+    assert_eq!(price.price, 1_0000000);
+    assert_eq!(price.timestamp, norm_timestamp);
+    assert!(price.fallback);
+}
@@ -0,0 +1,298 @@
+#![cfg(test)]
+
+use crate::testutils::{
+    create_mock_oracle, create_oracle_aggregator, setup_default_aggregator, EnvTestUtils,
+};
+use crate::types::Asset;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, Symbol, Vec};
+
+#[test]
+fn test_price() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset_0 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_1 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_2 = Asset::Other(Symbol::new(&e, "wETH"));
+    let asset_0 = Asset::Stellar(Address::generate(&e));
+    let asset_1 = Asset::Stellar(Address::generate(&e));
+    let asset_2 = Asset::Stellar(Address::generate(&e));
+
+    let (oracle_aggregator_client, oracle_1, oracle_2) = setup_default_aggregator(
+        &e,
+        &admin,
+        &base,
+        &oracle_asset_0,
+        &oracle_asset_1,
+        &oracle_asset_2,
+    );
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_120_000_000, 1_010_000_000]),
+        &(norm_timestamp - 300),
+    );
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_110_000_000, 1_000_000_000]),
+        &norm_timestamp,
+    );
+
+    let norm_timestamp_2 = e.ledger().timestamp() / 600 * 600;
+    oracle_2.set_price(
+        &Vec::from_array(&e, [1010_000000]),
+        &(norm_timestamp_2 - 600),
+    );
+    oracle_2.set_price(&Vec::from_array(&e, [1020_000000]), &norm_timestamp_2);
+
+    oracle_aggregator_client.add_asset(&asset_0, &oracle_1.address, &oracle_asset_0, &0);
+    oracle_aggregator_client.add_asset(&asset_2, &oracle_2.address, &oracle_asset_2, &0);
+    oracle_aggregator_client.add_base_asset(&asset_1);
+
+    // jump past max age to ensure historical prices are not bound by it
+    e.jump_time(1200);
+
+    let price_0 = oracle_aggregator_client
+        .price(&asset_0, &(norm_timestamp - 300))
+        .unwrap();
+    assert_eq!(price_0.price, 0_1200000);
+    assert_eq!(price_0.timestamp, norm_timestamp - 300);
+
+    let price_0 = oracle_aggregator_client
+        .price(&asset_0, &norm_timestamp)
+        .unwrap();
+    assert_eq!(price_0.price, 0_1100000);
+    assert_eq!(price_0.timestamp, norm_timestamp);
+
+    let price_2 = oracle_aggregator_client
+        .price(&asset_2, &(norm_timestamp_2 - 600))
+        .unwrap();
+    assert_eq!(price_2.price, 1010_0000000);
+    assert_eq!(price_2.timestamp, norm_timestamp_2 - 600);
+
+    // no round exists for the timestamp
+    let price_none = oracle_aggregator_client.price(&asset_0, &(norm_timestamp + 300));
+    assert!(price_none.is_none());
+
+    // base-like and base assets report a price of 1 at the requested timestamp
+    let price_1 = oracle_aggregator_client
+        .price(&asset_1, &norm_timestamp)
+        .unwrap();
+    assert_eq!(price_1.price, 1_0000000);
+    assert_eq!(price_1.timestamp, norm_timestamp);
+
+    let price_base = oracle_aggregator_client
+        .price(&base, &(norm_timestamp - 300))
+        .unwrap();
+    assert_eq!(price_base.price, 1_0000000);
+    assert_eq!(price_base.timestamp, norm_timestamp - 300);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #104)")]
+fn test_price_asset_not_found() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset_0 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_1 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_2 = Asset::Other(Symbol::new(&e, "wETH"));
+
+    let (oracle_aggregator_client, _, _) = setup_default_aggregator(
+        &e,
+        &admin,
+        &base,
+        &oracle_asset_0,
+        &oracle_asset_1,
+        &oracle_asset_2,
+    );
+
+    oracle_aggregator_client.price(
+        &Asset::Other(Symbol::new(&e, "NOT_FOUND")),
+        &e.ledger().timestamp(),
+    );
+}
+
+#[test]
+fn test_prices() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset_0 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_1 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_2 = Asset::Other(Symbol::new(&e, "wETH"));
+    let asset_0 = Asset::Stellar(Address::generate(&e));
+    let asset_1 = Asset::Stellar(Address::generate(&e));
+
+    let (oracle_aggregator_client, oracle_1, _) = setup_default_aggregator(
+        &e,
+        &admin,
+        &base,
+        &oracle_asset_0,
+        &oracle_asset_1,
+        &oracle_asset_2,
+    );
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_130_000_000, 1_020_000_000]),
+        &(norm_timestamp - 600),
+    );
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_120_000_000, 1_010_000_000]),
+        &(norm_timestamp - 300),
+    );
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_110_000_000, 1_000_000_000]),
+        &norm_timestamp,
+    );
+
+    oracle_aggregator_client.add_asset(&asset_0, &oracle_1.address, &oracle_asset_0, &0);
+    oracle_aggregator_client.add_base_asset(&asset_1);
+
+    let prices_0 = oracle_aggregator_client.prices(&asset_0, &3).unwrap();
+    assert_eq!(prices_0.len(), 3);
+    assert_eq!(prices_0.get_unchecked(0).price, 0_1100000);
+    assert_eq!(prices_0.get_unchecked(0).timestamp, norm_timestamp);
+    assert_eq!(prices_0.get_unchecked(1).price, 0_1200000);
+    assert_eq!(prices_0.get_unchecked(1).timestamp, norm_timestamp - 300);
+    assert_eq!(prices_0.get_unchecked(2).price, 0_1300000);
+    assert_eq!(prices_0.get_unchecked(2).timestamp, norm_timestamp - 600);
+
+    // jump 1600 seconds
+    // -> 3 records allows rounds within 2700 seconds
+    // -> 2 records allows rounds within 1800 seconds
+    // -> 1 record allows rounds within 900 seconds
+    e.jump_time(1600);
+
+    let prices_0 = oracle_aggregator_client.prices(&asset_0, &3).unwrap();
+    assert_eq!(prices_0.len(), 3);
+
+    let prices_0 = oracle_aggregator_client.prices(&asset_0, &2).unwrap();
+    assert_eq!(prices_0.len(), 1);
+    assert_eq!(prices_0.get_unchecked(0).price, 0_1100000);
+    assert_eq!(prices_0.get_unchecked(0).timestamp, norm_timestamp);
+
+    let prices_none = oracle_aggregator_client.prices(&asset_0, &1);
+    assert!(prices_none.is_none());
+
+    let prices_none = oracle_aggregator_client.prices(&asset_0, &0);
+    assert!(prices_none.is_none());
+
+    // base-like assets report a single price of 1 at the current timestamp
+    let prices_1 = oracle_aggregator_client.prices(&asset_1, &3).unwrap();
+    assert_eq!(prices_1.len(), 1);
+    assert_eq!(prices_1.get_unchecked(0).price, 1_0000000);
+    assert_eq!(prices_1.get_unchecked(0).timestamp, e.ledger().timestamp());
+}
+
+#[test]
+fn test_price_sanity_checks() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle.set_price(&vec![&e, 0], &(norm_timestamp - 900));
+    oracle.set_price(&vec![&e, 1_0000000], &(norm_timestamp - 600));
+    oracle.set_price(&vec![&e, -1_0000000], &(norm_timestamp - 300));
+    oracle.set_price(&vec![&e, 1_0100000], &norm_timestamp);
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle.address);
+    aggregator_client.add_asset(&asset, &oracle.address, &oracle_asset, &0);
+
+    // rounds with a price of zero or less are not reported
+    let price = aggregator_client.price(&asset, &norm_timestamp);
+    assert_eq!(price.unwrap().price, 1_0100000);
+    let price = aggregator_client.price(&asset, &(norm_timestamp - 300));
+    assert!(price.is_none());
+    let price = aggregator_client.price(&asset, &(norm_timestamp - 900));
+    assert!(price.is_none());
+    let price = aggregator_client.x_price(&asset, &base, &(norm_timestamp - 300));
+    assert!(price.is_none());
+
+    let prices = aggregator_client.prices(&asset, &4).unwrap();
+    assert_eq!(prices.len(), 2);
+    assert_eq!(prices.get_unchecked(0).price, 1_0100000);
+    assert_eq!(prices.get_unchecked(0).timestamp, norm_timestamp);
+    assert_eq!(prices.get_unchecked(1).price, 1_0000000);
+    assert_eq!(prices.get_unchecked(1).timestamp, norm_timestamp - 600);
+
+    let twap = aggregator_client.twap(&asset, &1);
+    assert_eq!(twap, Some(1_0100000));
+    let twap = aggregator_client.twap(&asset, &2);
+    assert!(twap.is_none());
+
+    // rounds too far past the ledger timestamp are not reported
+    oracle.set_price(&vec![&e, 1_0200000], &(norm_timestamp + 600));
+    let price = aggregator_client.price(&asset, &(norm_timestamp + 600));
+    assert!(price.is_none());
+    let prices = aggregator_client.prices(&asset, &1);
+    assert!(prices.is_none());
+    let twap = aggregator_client.twap(&asset, &1);
+    assert!(twap.is_none());
+    let twap = aggregator_client.x_twap(&asset, &base, &1);
+    assert!(twap.is_none());
+}
+
+#[test]
+fn test_resolution_and_last_timestamp() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset_0 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_1 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_2 = Asset::Other(Symbol::new(&e, "wETH"));
+
+    let (oracle_aggregator_client, oracle_1, oracle_2) = setup_default_aggregator(
+        &e,
+        &admin,
+        &base,
+        &oracle_asset_0,
+        &oracle_asset_1,
+        &oracle_asset_2,
+    );
+
+    // coarsest resolution of oracle_1 (300s) and oracle_2 (600s)
+    assert_eq!(oracle_aggregator_client.resolution(), 600);
+
+    let norm_timestamp = e.ledger().timestamp() / 600 * 600;
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_110_000_000, 1_000_000_000]),
+        &norm_timestamp,
+    );
+    oracle_2.set_price(&Vec::from_array(&e, [1010_000000]), &(norm_timestamp - 600));
+
+    // oldest fresh timestamp
+    assert_eq!(
+        oracle_aggregator_client.last_timestamp(),
+        norm_timestamp - 600
+    );
+
+    // oracle_2 is older than max_age
+    e.jump_time(301);
+    assert_eq!(oracle_aggregator_client.last_timestamp(), norm_timestamp);
+
+    // no oracle is within max_age
+    e.jump_time(600);
+    assert_eq!(oracle_aggregator_client.last_timestamp(), 0);
+}
@@ -3,7 +3,7 @@
 use crate::types::Asset;
 
 use crate::testutils::{create_oracle_aggregator, EnvTestUtils};
-use soroban_sdk::{testutils::Address as _, vec, Address, Env, IntoVal, Symbol, Val, Vec};
+use soroban_sdk::{testutils::Address as _, Address, Symbol};
 
 use super::snapshot;
 
@@ -55,7 +55,7 @@ fn test_reflector() {
 
     // set starting prices on the reflector contract
     round_timestamp += 300;
-    set_reflector_prices(
+    snapshot::set_reflector_prices(
         &e,
         0_3000000_0000000,
         1_1000000_0000000,
@@ -87,7 +87,7 @@ fn test_reflector() {
     // set round 1 prices on the reflector contract
     // -> EURC and AQUA no price data
     round_timestamp += 300;
-    set_reflector_prices(&e, 0_3100000_0000000, 0, 0, 1_0000011_0000000);
+    snapshot::set_reflector_prices(&e, 0_3100000_0000000, 0, 0, 1_0000011_0000000);
 
     // validate lastprice
     // -> EURC and AQUA did not have price data last round
@@ -111,7 +111,7 @@ fn test_reflector() {
     // set round 2 prices on the reflector contract
     e.jump_time(300);
     round_timestamp += 300;
-    set_reflector_prices(
+    snapshot::set_reflector_prices(
         &e,
         0_2900000_0000000,
         1_1100000_0000000,
@@ -141,7 +141,7 @@ fn test_reflector() {
     // -> EURC price spiked past 10% deviation
     e.jump_time(300);
     round_timestamp += 300;
-    set_reflector_prices(
+    snapshot::set_reflector_prices(
         &e,
         0_2800000_0000000,
         1_2300000_0000000,
@@ -169,7 +169,7 @@ fn test_reflector() {
     // set round 4 prices to ensure EURC can be fetched again
     e.jump_time(300);
     round_timestamp += 300;
-    set_reflector_prices(
+    snapshot::set_reflector_prices(
         &e,
         0_2700000_0000000,
         1_2400000_0000000,
@@ -217,7 +217,7 @@ fn test_reflector() {
 
     // run another update
     round_timestamp += 900;
-    set_reflector_prices(
+    snapshot::set_reflector_prices(
         &e,
         0_2600000_0000000,
         1_2500000_0000000,
@@ -254,35 +254,3 @@ fn test_reflector() {
     let result = aggregator_client.try_add_base_asset(&usdglo_asset);
     assert!(result.is_err());
 }
-
-/// Set prices on the reflector contract for the most recent round based on the
-/// current ledger timestamp.
-fn set_reflector_prices(
-    e: &Env,
-    xlm_price: i128,
-    eurc_price: i128,
-    aqua_price: i128,
-    usdglo_price: i128,
-) {
-    let timestamp: u64 = (e.ledger().timestamp() / 300u64) * 300u64 * 1000u64;
-    let reflector = Address::from_str(&e, snapshot::REFLECTOR);
-    let price_array: Vec<i128> = vec![
-        e,
-        1, // BTCLN
-        aqua_price,
-        1, // yUSDC
-        1, // FIDR
-        1, // SSLX
-        1, // ARST
-        1, // mykobo EURC
-        xlm_price,
-        1, // XRP
-        eurc_price,
-        1, // XRF
-        usdglo_price,
-        1, // CETES
-        1, // USTRY
-    ];
-    let args: Vec<Val> = vec![e, price_array.into_val(e), timestamp.into_val(e)];
-    e.invoke_contract::<Val>(&reflector, &Symbol::new(e, "set_price"), args);
-}
@@ -0,0 +1,157 @@
+#![cfg(test)]
+
+use crate::testutils::{assert_assets_equal, setup_default_aggregator, EnvTestUtils};
+use crate::types::Asset;
+use sep_40_oracle::{Asset as Sep40Asset, PriceFeedClient as Sep40Client};
+use soroban_sdk::{testutils::Address as _, Address, Env, Error, Symbol, Vec};
+
+/// Convert an asset to the asset type used by the standard SEP-40 client
+fn to_sep40_asset(asset: &Asset) -> Sep40Asset {
+    match asset {
+        Asset::Stellar(address) => Sep40Asset::Stellar(address.clone()),
+        Asset::Other(symbol) => Sep40Asset::Other(symbol.clone()),
+    }
+}
+
+/// Convert an asset returned by the standard SEP-40 client back to the aggregator asset type
+fn from_sep40_asset(asset: Sep40Asset) -> Asset {
+    match asset {
+        Sep40Asset::Stellar(address) => Asset::Stellar(address),
+        Sep40Asset::Other(symbol) => Asset::Other(symbol),
+    }
+}
+
+#[test]
+fn test_sep40_conformance() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset_0 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_1 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_2 = Asset::Other(Symbol::new(&e, "wETH"));
+    let asset_0 = Asset::Stellar(Address::generate(&e));
+    let asset_1 = Asset::Stellar(Address::generate(&e));
+    let asset_2 = Asset::Stellar(Address::generate(&e));
+
+    let (oracle_aggregator_client, oracle_1, oracle_2) = setup_default_aggregator(
+        &e,
+        &admin,
+        &base,
+        &oracle_asset_0,
+        &oracle_asset_1,
+        &oracle_asset_2,
+    );
+
+    // oracle_1 reports in 9 decimals, oracle_2 in 6 decimals
+    let norm_timestamp = e.ledger().timestamp() / 600 * 600;
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_120_000_009, 1_000_000_000]),
+        &(norm_timestamp - 300),
+    );
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_110_000_009, 1_000_000_000]),
+        &norm_timestamp,
+    );
+    oracle_2.set_price(&Vec::from_array(&e, [1010_123456]), &norm_timestamp);
+
+    oracle_aggregator_client.add_asset(&asset_0, &oracle_1.address, &oracle_asset_0, &0);
+    oracle_aggregator_client.add_asset(&asset_2, &oracle_2.address, &oracle_asset_2, &0);
+    oracle_aggregator_client.add_base_asset(&asset_1);
+
+    let sep40_client = Sep40Client::new(&e, &oracle_aggregator_client.address);
+
+    // metadata
+    assert_assets_equal(from_sep40_asset(sep40_client.base()), base.clone());
+    assert_eq!(sep40_client.decimals(), 7);
+    assert_eq!(sep40_client.resolution(), 600);
+    let assets = sep40_client.assets();
+    assert_eq!(assets.len(), 3);
+    assert_assets_equal(from_sep40_asset(assets.get_unchecked(0)), asset_1.clone());
+    assert_assets_equal(from_sep40_asset(assets.get_unchecked(1)), asset_0.clone());
+    assert_assets_equal(from_sep40_asset(assets.get_unchecked(2)), asset_2.clone());
+
+    // lastprice is normalized to the aggregator decimals
+    let price_0 = sep40_client.lastprice(&to_sep40_asset(&asset_0)).unwrap();
+    assert_eq!(price_0.price, 0_1100000);
+    assert_eq!(price_0.timestamp, norm_timestamp);
+    let price_2 = sep40_client.lastprice(&to_sep40_asset(&asset_2)).unwrap();
+    assert_eq!(price_2.price, 1010_1234560);
+    assert_eq!(price_2.timestamp, norm_timestamp);
+
+    // base and base-like assets are priced at 1
+    let price_base = sep40_client.lastprice(&to_sep40_asset(&base)).unwrap();
+    assert_eq!(price_base.price, 1_0000000);
+    assert_eq!(price_base.timestamp, e.ledger().timestamp());
+    let price_1 = sep40_client.lastprice(&to_sep40_asset(&asset_1)).unwrap();
+    assert_eq!(price_1.price, 1_0000000);
+    assert_eq!(price_1.timestamp, e.ledger().timestamp());
+
+    // price
+    let price_0 = sep40_client
+        .price(&to_sep40_asset(&asset_0), &(norm_timestamp - 300))
+        .unwrap();
+    assert_eq!(price_0.price, 0_1200000);
+    assert_eq!(price_0.timestamp, norm_timestamp - 300);
+    let price_1 = sep40_client
+        .price(&to_sep40_asset(&asset_1), &(norm_timestamp - 300))
+        .unwrap();
+    assert_eq!(price_1.price, 1_0000000);
+    assert_eq!(price_1.timestamp, norm_timestamp - 300);
+    assert!(sep40_client
+        .price(&to_sep40_asset(&asset_0), &(norm_timestamp - 600))
+        .is_none());
+
+    // prices
+    let prices_0 = sep40_client.prices(&to_sep40_asset(&asset_0), &2).unwrap();
+    assert_eq!(prices_0.len(), 2);
+    assert_eq!(prices_0.get_unchecked(0).price, 0_1100000);
+    assert_eq!(prices_0.get_unchecked(0).timestamp, norm_timestamp);
+    assert_eq!(prices_0.get_unchecked(1).price, 0_1200000);
+    assert_eq!(prices_0.get_unchecked(1).timestamp, norm_timestamp - 300);
+
+    // stale data is not reported
+    e.jump_time(901);
+    assert!(sep40_client.lastprice(&to_sep40_asset(&asset_0)).is_none());
+    assert!(sep40_client.lastprice(&to_sep40_asset(&asset_2)).is_none());
+    assert!(sep40_client.prices(&to_sep40_asset(&asset_0), &1).is_none());
+    let price_base = sep40_client.lastprice(&to_sep40_asset(&base)).unwrap();
+    assert_eq!(price_base.price, 1_0000000);
+    assert_eq!(price_base.timestamp, e.ledger().timestamp());
+}
+
+#[test]
+fn test_sep40_unknown_asset() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset_0 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_1 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_2 = Asset::Other(Symbol::new(&e, "wETH"));
+
+    let (oracle_aggregator_client, _, _) = setup_default_aggregator(
+        &e,
+        &admin,
+        &base,
+        &oracle_asset_0,
+        &oracle_asset_1,
+        &oracle_asset_2,
+    );
+
+    let sep40_client = Sep40Client::new(&e, &oracle_aggregator_client.address);
+    let unknown_asset = to_sep40_asset(&Asset::Other(Symbol::new(&e, "NOT_FOUND")));
+
+    let result = sep40_client.try_lastprice(&unknown_asset);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    let result = sep40_client.try_price(&unknown_asset, &e.ledger().timestamp());
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    let result = sep40_client.try_prices(&unknown_asset, &1);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+}
@@ -0,0 +1,965 @@
+#![cfg(test)]
+
+use crate::errors::OracleAggregatorErrors;
+use crate::testutils::{create_mock_oracle, create_oracle_aggregator, EnvTestUtils};
+use crate::types::{Aggregation, Asset, Reference};
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    vec, Address, Env, Error, IntoVal, Symbol,
+};
+
+#[test]
+fn test_lastprice_median() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_wrapped = Asset::Other(Symbol::new(&e, "wASSET"));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 9, 300);
+    let oracle_2 = create_mock_oracle(&e, &vec![&e, oracle_asset_wrapped.clone()], 6, 600);
+
+    let norm_timestamp = e.ledger().timestamp() / 600 * 600;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_050000000], &(norm_timestamp - 300));
+    oracle_2.set_price(&vec![&e, 0_950000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+    aggregator_client.add_oracle(&oracle_2.address);
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &0);
+    let source_price = aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "add_asset_source"),
+                    vec![
+                        &e,
+                        asset.into_val(&e),
+                        oracle_1.address.to_val(),
+                        oracle_asset.into_val(&e),
+                    ]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    assert_eq!(source_price.price, 1_0500000);
+    assert_eq!(source_price.timestamp, norm_timestamp - 300);
+
+    // median of 2 sources
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0250000);
+    assert_eq!(price.timestamp, norm_timestamp - 300);
+
+    aggregator_client.add_asset_source(&asset, &oracle_2.address, &oracle_asset_wrapped);
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.sources.len(), 2);
+    assert_eq!(config.sources.get_unchecked(0).oracle_index, 1);
+    assert_eq!(config.sources.get_unchecked(1).oracle_index, 2);
+
+    // median of 3 sources
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0000000);
+    assert_eq!(price.timestamp, norm_timestamp - 300);
+
+    // oracle_1 price is older than max_age, and is ignored
+    e.jump_time(601);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 0_9750000);
+    assert_eq!(price.timestamp, norm_timestamp);
+
+    // no source is fresh
+    e.jump_time(300);
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+}
+
+#[test]
+fn test_add_asset_source_errors() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_2 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_2.set_price(&vec![&e, 1_0000000], &(norm_timestamp - 1200));
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_2.address);
+
+    // asset does not exist
+    let result = aggregator_client.try_add_asset_source(&asset, &oracle_2.address, &oracle_asset);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &0);
+
+    // oracle does not exist
+    let result = aggregator_client.try_add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(107))));
+
+    // oracle is already used by the asset
+    let result = aggregator_client.try_add_asset_source(&asset, &oracle_0.address, &oracle_asset);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(109))));
+
+    // source has no recent price
+    let result = aggregator_client.try_add_asset_source(&asset, &oracle_2.address, &oracle_asset);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(101))));
+
+    aggregator_client.add_oracle(&oracle_1.address);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+
+    let result = aggregator_client.try_add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(109))));
+}
+
+#[test]
+fn test_lastprice_weighted_mean() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_2 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_1000000], &(norm_timestamp - 300));
+    oracle_2.set_price(&vec![&e, 0_9000000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+    aggregator_client.add_oracle(&oracle_2.address);
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &0);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+    aggregator_client.add_asset_source(&asset, &oracle_2.address, &oracle_asset);
+
+    aggregator_client.set_aggregation(&asset, &Aggregation::WeightedMean, &vec![&e, 4, 3, 1]);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_aggregation"),
+                    vec![
+                        &e,
+                        asset.into_val(&e),
+                        Aggregation::WeightedMean.into_val(&e),
+                        vec![&e, 4u32, 3u32, 1u32].into_val(&e),
+                    ]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.aggregation, Aggregation::WeightedMean);
+    assert_eq!(config.weight, 4);
+    assert_eq!(config.sources.get_unchecked(0).weight, 3);
+    assert_eq!(config.sources.get_unchecked(1).weight, 1);
+
+    // (4 * 1.0 + 3 * 1.1 + 1 * 0.9) / 8
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0250000);
+    assert_eq!(price.timestamp, norm_timestamp - 300);
+
+    // oracle_1 is older than max_age, and its weight is excluded
+    // -> (4 * 1.0 + 1 * 0.9) / 5
+    e.jump_time(601);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 0_9800000);
+    assert_eq!(price.timestamp, norm_timestamp);
+
+    // a source with a weight of zero is not used
+    aggregator_client.set_aggregation(&asset, &Aggregation::WeightedMean, &vec![&e, 0, 3, 1]);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 0_9000000);
+
+    // switching back to median ignores weights
+    aggregator_client.set_aggregation(&asset, &Aggregation::Median, &vec![&e, 0, 3, 1]);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 0_9500000);
+}
+
+#[test]
+fn test_set_aggregation_errors() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+
+    // asset does not exist
+    let result =
+        aggregator_client.try_set_aggregation(&asset, &Aggregation::WeightedMean, &vec![&e, 1]);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &0);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+
+    // weights do not match the sources
+    let result =
+        aggregator_client.try_set_aggregation(&asset, &Aggregation::WeightedMean, &vec![&e, 1]);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(111))));
+    let result = aggregator_client.try_set_aggregation(
+        &asset,
+        &Aggregation::WeightedMean,
+        &vec![&e, 1, 1, 1],
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(111))));
+
+    // all weights are zero
+    let result =
+        aggregator_client.try_set_aggregation(&asset, &Aggregation::WeightedMean, &vec![&e, 0, 0]);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(111))));
+}
+
+#[test]
+fn test_lastprice_fallback() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_2 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &(norm_timestamp - 300));
+    oracle_0.set_price(&vec![&e, 1_0100000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_1000000], &(norm_timestamp - 300));
+    oracle_1.set_price(&vec![&e, 1_1100000], &norm_timestamp);
+    oracle_2.set_price(&vec![&e, 0_9000000], &(norm_timestamp - 300));
+    oracle_2.set_price(&vec![&e, 0_9100000], &norm_timestamp);
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+    aggregator_client.add_oracle(&oracle_2.address);
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &10);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+    aggregator_client.add_asset_source(&asset, &oracle_2.address, &oracle_asset);
+
+    // median reports every source used
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.price, 1_0100000);
+    assert_eq!(price.timestamp, norm_timestamp);
+    assert_eq!(price.sources, vec![&e, 0, 1, 2]);
+
+    aggregator_client.set_aggregation(&asset, &Aggregation::Fallback, &vec![&e, 1, 1, 1]);
+
+    // primary oracle is used
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.price, 1_0100000);
+    assert_eq!(price.timestamp, norm_timestamp);
+    assert_eq!(price.sources, vec![&e, 0]);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0100000);
+    assert_eq!(price.timestamp, norm_timestamp);
+
+    // primary oracle breaks the max deviation check, first additional source is used
+    e.jump_time(300);
+    oracle_0.set_price(&vec![&e, 1_5000000], &(norm_timestamp + 300));
+    oracle_1.set_price(&vec![&e, 1_1200000], &(norm_timestamp + 300));
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.price, 1_1200000);
+    assert_eq!(price.timestamp, norm_timestamp + 300);
+    assert_eq!(price.sources, vec![&e, 1]);
+
+    // first additional source is stale, second additional source is used
+    e.jump_time(900);
+    oracle_0.set_price(&vec![&e, 2_0000000], &(norm_timestamp + 1200));
+    oracle_2.set_price(&vec![&e, 0_9150000], &(norm_timestamp + 900));
+    oracle_2.set_price(&vec![&e, 0_9200000], &(norm_timestamp + 1200));
+    e.jump_time(1);
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.price, 0_9200000);
+    assert_eq!(price.timestamp, norm_timestamp + 1200);
+    assert_eq!(price.sources, vec![&e, 2]);
+
+    // no source can report a price
+    e.jump_time(900);
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(result.err(), Some(Ok(OracleAggregatorErrors::NoValidPrice)));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+
+    // base assets do not use any sources
+    let price = aggregator_client.aggregate_lastprice(&base);
+    assert_eq!(price.price, 1_0000000);
+    assert_eq!(price.sources.len(), 0);
+}
+
+#[test]
+fn test_lastprice_reference_divergence() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 9, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_040000000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &0);
+    let reference_price =
+        aggregator_client.set_reference(&asset, &oracle_1.address, &oracle_asset, &5);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_reference"),
+                    vec![
+                        &e,
+                        asset.into_val(&e),
+                        oracle_1.address.to_val(),
+                        oracle_asset.into_val(&e),
+                        5u32.into_val(&e),
+                    ]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    assert_eq!(reference_price.price, 1_0400000);
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    let reference = match config.reference {
+        Reference::Oracle(reference) => reference,
+        Reference::None => panic!("Reference not set"),
+    };
+    assert_eq!(reference.oracle_index, 1);
+    assert_eq!(reference.max_divergence, 5);
+
+    // price is reported from the primary oracle
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0000000);
+    assert_eq!(price.timestamp, norm_timestamp);
+
+    // prices diverge by 5%
+    e.jump_time(300);
+    oracle_0.set_price(&vec![&e, 0_9880000], &(norm_timestamp + 300));
+    oracle_1.set_price(&vec![&e, 1_040000000], &(norm_timestamp + 300));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::MaxDivergenceExceeded))
+    );
+
+    // prices diverge by less than 5%
+    e.jump_time(300);
+    oracle_0.set_price(&vec![&e, 0_9890000], &(norm_timestamp + 600));
+    oracle_1.set_price(&vec![&e, 1_040000000], &(norm_timestamp + 600));
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 0_9890000);
+    assert_eq!(price.timestamp, norm_timestamp + 600);
+
+    // reference oracle cannot report a price
+    e.jump_time(300);
+    oracle_0.set_price(&vec![&e, 0_9890000], &(norm_timestamp + 900));
+    e.jump_time(601);
+    oracle_0.set_price(&vec![&e, 0_9890000], &(norm_timestamp + 1500));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::ReferenceUnavailable))
+    );
+}
+
+#[test]
+fn test_set_reference_errors() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_2 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_2.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+    aggregator_client.add_oracle(&oracle_2.address);
+
+    // asset does not exist
+    let result = aggregator_client.try_set_reference(&asset, &oracle_1.address, &oracle_asset, &5);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &0);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+
+    // reference oracle is a source
+    let result = aggregator_client.try_set_reference(&asset, &oracle_0.address, &oracle_asset, &5);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(109))));
+    let result = aggregator_client.try_set_reference(&asset, &oracle_1.address, &oracle_asset, &5);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(109))));
+
+    // invalid max divergence
+    let result = aggregator_client.try_set_reference(&asset, &oracle_2.address, &oracle_asset, &0);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(112))));
+    let result =
+        aggregator_client.try_set_reference(&asset, &oracle_2.address, &oracle_asset, &100);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(112))));
+
+    aggregator_client.set_reference(&asset, &oracle_2.address, &oracle_asset, &5);
+
+    // reference oracle cannot be added as a source
+    let result = aggregator_client.try_add_asset_source(&asset, &oracle_2.address, &oracle_asset);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(109))));
+}
+
+#[test]
+fn test_lastprice_min_sources() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_2 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_0200000], &(norm_timestamp - 600));
+    oracle_2.set_price(&vec![&e, 0_9800000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+    aggregator_client.add_oracle(&oracle_2.address);
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &0);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+    aggregator_client.add_asset_source(&asset, &oracle_2.address, &oracle_asset);
+
+    aggregator_client.set_min_sources(&asset, &2);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_min_sources"),
+                    vec![&e, asset.into_val(&e), 2u32.into_val(&e)]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.min_sources, 2);
+
+    // 3 of 3 sources are fresh
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.price, 1_0000000);
+    assert_eq!(price.sources, vec![&e, 0, 1, 2]);
+
+    // 2 of 3 sources are fresh
+    e.jump_time(301);
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.price, 0_9900000);
+    assert_eq!(price.timestamp, norm_timestamp);
+    assert_eq!(price.sources, vec![&e, 0, 2]);
+
+    // fallback uses the first price once 2 sources are fresh
+    aggregator_client.set_aggregation(&asset, &Aggregation::Fallback, &vec![&e, 1, 1, 1]);
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.price, 1_0000000);
+    assert_eq!(price.sources, vec![&e, 0, 2]);
+
+    // 1 of 3 sources are fresh
+    aggregator_client.set_aggregation(&asset, &Aggregation::Median, &vec![&e, 1, 1, 1]);
+    e.jump_time(600);
+    oracle_2.set_price(&vec![&e, 0_9800000], &(norm_timestamp + 600));
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(result.err(), Some(Ok(OracleAggregatorErrors::QuorumNotMet)));
+    let price = aggregator_client.lastprice(&asset);
+    assert!(price.is_none());
+
+    // quorum of 1 reports the fresh source
+    aggregator_client.set_min_sources(&asset, &1);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 0_9800000);
+    assert_eq!(price.timestamp, norm_timestamp + 600);
+}
+
+#[test]
+fn test_set_min_sources_errors() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+
+    // asset does not exist
+    let result = aggregator_client.try_set_min_sources(&asset, &1);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &0);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+
+    // invalid min sources
+    let result = aggregator_client.try_set_min_sources(&asset, &0);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(113))));
+    let result = aggregator_client.try_set_min_sources(&asset, &3);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(113))));
+
+    aggregator_client.set_min_sources(&asset, &2);
+}
+
+#[test]
+fn test_lastprice_outlier_filter() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 9, 300);
+    let oracle_2 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 6, 300);
+    let oracle_3 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_4 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 8, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_010000000], &norm_timestamp);
+    oracle_2.set_price(&vec![&e, 1_500000], &norm_timestamp);
+    oracle_3.set_price(&vec![&e, 0_9900000], &norm_timestamp);
+    oracle_4.set_price(&vec![&e, 1_03000000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+    aggregator_client.add_oracle(&oracle_2.address);
+    aggregator_client.add_oracle(&oracle_3.address);
+    aggregator_client.add_oracle(&oracle_4.address);
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &0);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+    aggregator_client.add_asset_source(&asset, &oracle_2.address, &oracle_asset);
+    aggregator_client.add_asset_source(&asset, &oracle_3.address, &oracle_asset);
+    aggregator_client.add_asset_source(&asset, &oracle_4.address, &oracle_asset);
+    aggregator_client.set_aggregation(&asset, &Aggregation::WeightedMean, &vec![&e, 1, 1, 1, 1, 1]);
+
+    // outlier is included without a filter
+    // -> (1.0 + 1.01 + 1.5 + 0.99 + 1.03) / 5
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.price, 1_1060000);
+    assert_eq!(price.sources, vec![&e, 0, 1, 2, 3, 4]);
+
+    aggregator_client.set_outlier_filter(&asset, &3);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "set_outlier_filter"),
+                    vec![&e, asset.into_val(&e), 3u32.into_val(&e)]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.max_mads, 3);
+
+    // median = 1.01, MAD = 0.02, outlier is more than 3 MADs away
+    // -> (1.0 + 1.01 + 0.99 + 1.03) / 4
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.price, 1_0075000);
+    assert_eq!(price.sources, vec![&e, 0, 1, 3, 4]);
+
+    // dropped outliers do not count towards the quorum
+    aggregator_client.set_min_sources(&asset, &5);
+    let result = aggregator_client.try_aggregate_lastprice(&asset);
+    assert_eq!(
+        result.err(),
+        Some(Ok(OracleAggregatorErrors::TooManyOutliers))
+    );
+    aggregator_client.set_min_sources(&asset, &1);
+
+    // filter is skipped with fewer than 3 prices
+    e.jump_time(901);
+    oracle_0.set_price(&vec![&e, 1_0000000], &(norm_timestamp + 900));
+    oracle_2.set_price(&vec![&e, 1_500000], &(norm_timestamp + 900));
+    let price = aggregator_client.aggregate_lastprice(&asset);
+    assert_eq!(price.price, 1_2500000);
+    assert_eq!(price.sources, vec![&e, 0, 2]);
+
+    // filter can be disabled
+    aggregator_client.set_outlier_filter(&asset, &0);
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.max_mads, 0);
+}
+
+#[test]
+fn test_confidence() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 9, 300);
+    let oracle_2 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 6, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_020000000], &(norm_timestamp - 300));
+    oracle_2.set_price(&vec![&e, 0_990000], &norm_timestamp);
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+    aggregator_client.add_oracle(&oracle_2.address);
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &0);
+
+    // single source has no spread
+    let confidence = aggregator_client.confidence(&asset);
+    assert_eq!(confidence.price, 1_0000000);
+    assert_eq!(confidence.timestamp, norm_timestamp);
+    assert_eq!(confidence.min, 1_0000000);
+    assert_eq!(confidence.max, 1_0000000);
+    assert_eq!(confidence.spread, 0);
+    assert_eq!(confidence.sources, 1);
+
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+    aggregator_client.add_asset_source(&asset, &oracle_2.address, &oracle_asset);
+
+    // (1.02 - 0.99) / 1.0
+    let confidence = aggregator_client.confidence(&asset);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(confidence.price, price.price);
+    assert_eq!(confidence.timestamp, price.timestamp);
+    assert_eq!(confidence.price, 1_0000000);
+    assert_eq!(confidence.timestamp, norm_timestamp - 300);
+    assert_eq!(confidence.min, 0_9900000);
+    assert_eq!(confidence.max, 1_0200000);
+    assert_eq!(confidence.spread, 300);
+    assert_eq!(confidence.sources, 3);
+
+    // stale sources are not included
+    // -> (1.0 - 0.99) / 0.995
+    e.jump_time(601);
+    let confidence = aggregator_client.confidence(&asset);
+    assert_eq!(confidence.price, 0_9950000);
+    assert_eq!(confidence.min, 0_9900000);
+    assert_eq!(confidence.max, 1_0000000);
+    assert_eq!(confidence.spread, 100);
+    assert_eq!(confidence.sources, 2);
+
+    // base asset
+    let confidence = aggregator_client.confidence(&base);
+    assert_eq!(confidence.price, 1_0000000);
+    assert_eq!(confidence.min, 1_0000000);
+    assert_eq!(confidence.max, 1_0000000);
+    assert_eq!(confidence.spread, 0);
+    assert_eq!(confidence.sources, 0);
+
+    // no source can report a price
+    e.jump_time(300);
+    let result = aggregator_client.try_confidence(&asset);
+    assert_eq!(result.err(), Some(Ok(OracleAggregatorErrors::NoValidPrice)));
+}
+
+#[test]
+fn test_remove_asset_source() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_2 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_0500000], &norm_timestamp);
+    oracle_2.set_price(&vec![&e, 1_2000000], &norm_timestamp);
+
+    let (aggregator_id, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+    aggregator_client.add_oracle(&oracle_2.address);
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &0);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+    aggregator_client.add_asset_source(&asset, &oracle_2.address, &oracle_asset);
+    aggregator_client.set_min_sources(&asset, &3);
+
+    // median of 3 sources
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0500000);
+
+    aggregator_client.remove_asset_source(&asset, &oracle_1.address);
+    assert_eq!(
+        e.auths()[0],
+        (
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    aggregator_id.clone(),
+                    Symbol::new(&e, "remove_asset_source"),
+                    vec![&e, asset.into_val(&e), oracle_1.address.to_val()]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.sources.len(), 1);
+    assert_eq!(config.sources.get_unchecked(0).oracle_index, 2);
+    assert_eq!(config.min_sources, 2);
+
+    // median of the 2 remaining sources
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_1000000);
+
+    aggregator_client.remove_asset_source(&asset, &oracle_2.address);
+    let config = aggregator_client
+        .asset_configs()
+        .get(asset.clone())
+        .unwrap();
+    assert_eq!(config.sources.len(), 0);
+    assert_eq!(config.min_sources, 1);
+
+    // only the primary oracle is left
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0000000);
+
+    // a removed source can be added again
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_0250000);
+}
+
+#[test]
+fn test_remove_asset_source_errors() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_2 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+    oracle_2.set_price(&vec![&e, 1_0000000], &norm_timestamp);
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+
+    // asset does not exist
+    let result = aggregator_client.try_remove_asset_source(&asset, &oracle_1.address);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(104))));
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &0);
+
+    // oracle does not exist
+    let result = aggregator_client.try_remove_asset_source(&asset, &oracle_2.address);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(107))));
+
+    aggregator_client.add_oracle(&oracle_2.address);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+
+    // oracle is not a source of the asset
+    let result = aggregator_client.try_remove_asset_source(&asset, &oracle_2.address);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(135))));
+
+    // the primary oracle cannot be removed
+    let result = aggregator_client.try_remove_asset_source(&asset, &oracle_0.address);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(135))));
+
+    aggregator_client.remove_asset_source(&asset, &oracle_1.address);
+
+    // source was already removed
+    let result = aggregator_client.try_remove_asset_source(&asset, &oracle_1.address);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(135))));
+}
+
+#[test]
+fn test_historical_prices_use_primary_oracle() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset = Asset::Stellar(Address::generate(&e));
+    let asset = Asset::Stellar(Address::generate(&e));
+
+    let oracle_0 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+    let oracle_1 = create_mock_oracle(&e, &vec![&e, oracle_asset.clone()], 7, 300);
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_0.set_price(&vec![&e, 1_0000000], &(norm_timestamp - 300));
+    oracle_0.set_price(&vec![&e, 1_2000000], &norm_timestamp);
+    oracle_1.set_price(&vec![&e, 2_0000000], &(norm_timestamp - 300));
+    oracle_1.set_price(&vec![&e, 2_2000000], &norm_timestamp);
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &admin, &base, &7, &900);
+    aggregator_client.add_oracle(&oracle_0.address);
+    aggregator_client.add_oracle(&oracle_1.address);
+
+    aggregator_client.add_asset(&asset, &oracle_0.address, &oracle_asset, &0);
+    aggregator_client.add_asset_source(&asset, &oracle_1.address, &oracle_asset);
+    aggregator_client.set_min_sources(&asset, &2);
+
+    // the last price is aggregated over both sources
+    let price = aggregator_client.lastprice(&asset).unwrap();
+    assert_eq!(price.price, 1_7000000);
+
+    // historical prices only use the primary oracle
+    let price = aggregator_client.price(&asset, &norm_timestamp).unwrap();
+    assert_eq!(price.price, 1_2000000);
+    let prices = aggregator_client.prices(&asset, &2).unwrap();
+    assert_eq!(prices.len(), 2);
+    assert_eq!(prices.get_unchecked(0).price, 1_2000000);
+    assert_eq!(prices.get_unchecked(1).price, 1_0000000);
+    let twap = aggregator_client.twap(&asset, &2).unwrap();
+    assert_eq!(twap, 1_1000000);
+}
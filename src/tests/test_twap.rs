@@ -0,0 +1,134 @@
+#![cfg(test)]
+
+use crate::testutils::{create_oracle_aggregator, setup_default_aggregator, EnvTestUtils};
+use crate::types::Asset;
+use soroban_sdk::{testutils::Address as _, Address, Env, Symbol, Vec};
+
+use super::snapshot;
+
+#[test]
+fn test_twap() {
+    let e = Env::default();
+    e.set_default_info();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let oracle_asset_0 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_1 = Asset::Stellar(Address::generate(&e));
+    let oracle_asset_2 = Asset::Other(Symbol::new(&e, "wETH"));
+    let asset_0 = Asset::Stellar(Address::generate(&e));
+    let asset_1 = Asset::Stellar(Address::generate(&e));
+    let asset_3 = Asset::Stellar(Address::generate(&e));
+
+    let (oracle_aggregator_client, oracle_1, _) = setup_default_aggregator(
+        &e,
+        &admin,
+        &base,
+        &oracle_asset_0,
+        &oracle_asset_1,
+        &oracle_asset_2,
+    );
+
+    let norm_timestamp = e.ledger().timestamp() / 300 * 300;
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_300_000_000, 2_000_000_000]),
+        &(norm_timestamp - 600),
+    );
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_200_000_000, 2_100_000_000]),
+        &(norm_timestamp - 300),
+    );
+    oracle_1.set_price(
+        &Vec::from_array(&e, [0_100_000_000, 1_900_000_000]),
+        &norm_timestamp,
+    );
+
+    oracle_aggregator_client.add_asset(&asset_0, &oracle_1.address, &oracle_asset_0, &0);
+    oracle_aggregator_client.add_asset(&asset_1, &oracle_1.address, &oracle_asset_1, &0);
+    oracle_aggregator_client.add_base_asset(&asset_3);
+
+    let twap_0 = oracle_aggregator_client.twap(&asset_0, &3).unwrap();
+    assert_eq!(twap_0, 0_2000000);
+    let twap_0 = oracle_aggregator_client.twap(&asset_0, &2).unwrap();
+    assert_eq!(twap_0, 0_1500000);
+    let twap_1 = oracle_aggregator_client.twap(&asset_1, &3).unwrap();
+    assert_eq!(twap_1, 2_0000000);
+
+    let x_twap = oracle_aggregator_client
+        .x_twap(&asset_0, &asset_1, &3)
+        .unwrap();
+    assert_eq!(x_twap, 0_1000000);
+
+    // base-like assets are priced at 1
+    let twap_3 = oracle_aggregator_client.twap(&asset_3, &3).unwrap();
+    assert_eq!(twap_3, 1_0000000);
+    let x_twap = oracle_aggregator_client
+        .x_twap(&asset_3, &asset_1, &3)
+        .unwrap();
+    assert_eq!(x_twap, 0_5000000);
+
+    // not enough rounds exist
+    let twap_none = oracle_aggregator_client.twap(&asset_0, &4);
+    assert!(twap_none.is_none());
+    let twap_none = oracle_aggregator_client.twap(&asset_0, &0);
+    assert!(twap_none.is_none());
+
+    // jump so the oldest of 3 rounds is older than 2700 seconds
+    e.jump_time(2101);
+    let twap_none = oracle_aggregator_client.twap(&asset_0, &3);
+    assert!(twap_none.is_none());
+    let x_twap_none = oracle_aggregator_client.x_twap(&asset_0, &asset_1, &3);
+    assert!(x_twap_none.is_none());
+}
+
+#[test]
+fn test_twap_missing_round() {
+    // mock oracle stops fetching `prices` at the first missing round, so use the snapshot
+    let e = snapshot::env_from_snapshot();
+    e.mock_all_auths();
+    let base = Asset::Other(Symbol::new(&e, "BASE"));
+
+    let xlm_address = Address::from_str(&e, snapshot::XLM);
+    let xlm_asset = Asset::Stellar(xlm_address.clone());
+
+    let bombadil = Address::generate(&e);
+    let reflector = Address::from_str(&e, snapshot::REFLECTOR);
+
+    let (_, aggregator_client) = create_oracle_aggregator(&e, &bombadil, &base, &7, &900);
+    aggregator_client.add_oracle(&reflector);
+    aggregator_client.add_asset(&xlm_asset, &reflector, &xlm_asset, &0);
+
+    let mut round_timestamp = snapshot::LAST_UPDATE_TIMESTAMP;
+    e.jump_time(300);
+    round_timestamp += 300;
+    snapshot::set_reflector_prices(&e, 0_3000000_0000000, 1, 1, 1);
+
+    // skip a round
+    e.jump_time(600);
+    round_timestamp += 600;
+    snapshot::set_reflector_prices(&e, 0_1000000_0000000, 1, 1, 1);
+
+    let twap_0 = aggregator_client.twap(&xlm_asset, &1).unwrap();
+    assert_eq!(twap_0, 0_1000000);
+
+    let twap_none = aggregator_client.twap(&xlm_asset, &2);
+    assert!(twap_none.is_none());
+
+    let twap_none = aggregator_client.twap(&xlm_asset, &3);
+    assert!(twap_none.is_none());
+
+    // prices still reports the rounds that exist
+    let prices = aggregator_client.prices(&xlm_asset, &3).unwrap();
+    assert_eq!(prices.len(), 2);
+    assert_eq!(prices.get_unchecked(0).price, 0_1000000);
+    assert_eq!(prices.get_unchecked(0).timestamp, round_timestamp);
+    assert_eq!(prices.get_unchecked(1).price, 0_3000000);
+    assert_eq!(prices.get_unchecked(1).timestamp, round_timestamp - 600);
+
+    // twap is reported once the rounds are contiguous again
+    e.jump_time(300);
+    snapshot::set_reflector_prices(&e, 0_2000000_0000000, 1, 1, 1);
+    let twap_1 = aggregator_client.twap(&xlm_asset, &2).unwrap();
+    assert_eq!(twap_1, 0_1500000);
+}
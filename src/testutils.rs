@@ -2,7 +2,7 @@
 
 use crate::{
     contract::OracleAggregatorClient,
-    types::{Asset, AssetConfig, OracleConfig},
+    types::{Asset, AssetConfig, OracleConfig, PriceBounds, Reference},
     OracleAggregator,
 };
 use sep_40_oracle::testutils::{Asset as MockAsset, MockPriceOracleClient, MockPriceOracleWASM};
@@ -144,6 +144,30 @@ pub fn setup_default_aggregator<'a>(
     return (aggregator_client, oracle_0_1, oracle_2);
 }
 
+/// Deploy a mock oracle that reports prices for the given assets
+pub fn create_mock_oracle<'a>(
+    e: &Env,
+    assets: &Vec<Asset>,
+    decimals: u32,
+    resolution: u32,
+) -> MockPriceOracleClient<'a> {
+    let oracle_id = Address::generate(&e);
+    e.register_at(&oracle_id, MockPriceOracleWASM, ());
+    let oracle = MockPriceOracleClient::new(&e, &oracle_id);
+    let mut mock_assets: Vec<MockAsset> = Vec::new(&e);
+    for asset in assets.iter() {
+        mock_assets.push_back(MockAsset::from(asset));
+    }
+    oracle.set_data(
+        &Address::generate(&e),
+        &MockAsset::Other(Symbol::new(&e, "BASE")),
+        &mock_assets,
+        &decimals,
+        &resolution,
+    );
+    oracle
+}
+
 pub fn assert_assets_equal(a: Asset, b: Asset) {
     match (a, b) {
         (Asset::Stellar(a), Asset::Stellar(b)) => assert_eq!(a, b),
@@ -156,6 +180,47 @@ pub fn assert_asset_config_equal(a: AssetConfig, b: AssetConfig) {
     assert_assets_equal(a.asset, b.asset);
     assert_eq!(a.oracle_index, b.oracle_index);
     assert_eq!(a.max_dev, b.max_dev);
+    assert_eq!(a.max_dev_bps, b.max_dev_bps);
+    assert_eq!(a.max_dev_up_bps, b.max_dev_up_bps);
+    assert_eq!(a.max_dev_down_bps, b.max_dev_down_bps);
+    assert_eq!(a.weight, b.weight);
+    assert_eq!(a.aggregation, b.aggregation);
+    assert_eq!(a.min_sources, b.min_sources);
+    assert_eq!(a.max_mads, b.max_mads);
+    assert_eq!(a.dev_rounds, b.dev_rounds);
+    assert_eq!(a.dev_mode, b.dev_mode);
+    assert_eq!(a.round_tolerance, b.round_tolerance);
+    assert_eq!(a.max_step_bps, b.max_step_bps);
+    assert_eq!(a.step_window, b.step_window);
+    assert_eq!(a.max_roc_bps, b.max_roc_bps);
+    assert_eq!(a.roc_window, b.roc_window);
+    assert_eq!(a.max_age, b.max_age);
+    assert_eq!(a.circuit_breaker, b.circuit_breaker);
+    assert_eq!(a.grace_period, b.grace_period);
+    match (a.bounds, b.bounds) {
+        (PriceBounds::Bounded(a_bounds), PriceBounds::Bounded(b_bounds)) => {
+            assert_eq!(a_bounds.min_price, b_bounds.min_price);
+            assert_eq!(a_bounds.max_price, b_bounds.max_price);
+            assert_eq!(a_bounds.policy, b_bounds.policy);
+        }
+        (PriceBounds::None, PriceBounds::None) => {}
+        _ => assert!(false, "Price bounds mismatch"),
+    };
+    match (a.reference, b.reference) {
+        (Reference::Oracle(a_reference), Reference::Oracle(b_reference)) => {
+            assert_assets_equal(a_reference.asset, b_reference.asset);
+            assert_eq!(a_reference.oracle_index, b_reference.oracle_index);
+            assert_eq!(a_reference.max_divergence, b_reference.max_divergence);
+        }
+        (Reference::None, Reference::None) => {}
+        _ => assert!(false, "Reference mismatch"),
+    };
+    assert_eq!(a.sources.len(), b.sources.len());
+    for (a_source, b_source) in a.sources.iter().zip(b.sources.iter()) {
+        assert_assets_equal(a_source.asset, b_source.asset);
+        assert_eq!(a_source.oracle_index, b_source.oracle_index);
+        assert_eq!(a_source.weight, b_source.weight);
+    }
 }
 
 pub fn assert_oracle_config_equal(a: OracleConfig, b: OracleConfig) {
@@ -163,6 +228,7 @@ pub fn assert_oracle_config_equal(a: OracleConfig, b: OracleConfig) {
     assert_eq!(a.index, b.index);
     assert_eq!(a.resolution, b.resolution);
     assert_eq!(a.decimals, b.decimals);
+    assert_eq!(a.liveness_rounds, b.liveness_rounds);
 }
 
 impl From<Asset> for MockAsset {
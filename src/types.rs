@@ -8,6 +8,48 @@ pub struct PriceData {
     pub timestamp: u64,
 }
 
+/// Aggregated price data for an asset, along with the sources used to calculate it
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AggregatePriceData {
+    pub price: i128,
+    pub timestamp: u64,
+    /// The indexes of the oracles whose prices were used
+    pub sources: Vec<u32>,
+    /// If the price is the last accepted price, reported because the sources could not report
+    /// a price
+    pub fallback: bool,
+}
+
+/// Aggregated price data for an asset, along with a confidence band of the sources used
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PriceConfidence {
+    pub price: i128,
+    pub timestamp: u64,
+    /// The lowest price reported by a source used
+    pub min: i128,
+    /// The highest price reported by a source used
+    pub max: i128,
+    /// The spread between the min and max price, in basis points of the price
+    pub spread: u32,
+    /// The number of sources used
+    pub sources: u32,
+    /// If the price is the last accepted price, reported because the sources could not report
+    /// a price
+    pub fallback: bool,
+}
+
+/// The state of a tripped circuit breaker of an asset
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BreakerState {
+    /// The ledger timestamp the breaker was tripped at
+    pub tripped_at: u64,
+    /// The error code of the failure that tripped the breaker
+    pub reason: u32,
+}
+
 /// Asset type
 #[contracttype]
 #[derive(Clone, PartialEq, Debug)]
@@ -27,6 +69,163 @@ pub struct AssetConfig {
     /// (e.g 5 => 5%). If this is 0, the oracle will just fetch the last price within the
     /// resolution time.
     pub max_dev: u32,
+    /// The maximum deviation allowed for a stable price, in basis points (e.g 30 => 0.3%). If
+    /// this is 0, `max_dev` is used instead.
+    pub max_dev_bps: u32,
+    /// The maximum upward deviation allowed for a stable price, in basis points. If this is 0,
+    /// the symmetric max deviation is used instead.
+    pub max_dev_up_bps: u32,
+    /// The maximum downward deviation allowed for a stable price, in basis points. If this is 0,
+    /// the symmetric max deviation is used instead.
+    pub max_dev_down_bps: u32,
+    /// Additional source oracles used to price the asset. If any exist, the last price is
+    /// aggregated from the fresh prices reported by the primary oracle and each additional source.
+    pub sources: Vec<SourceConfig>,
+    /// The weight of the primary oracle when using a weighted aggregation
+    pub weight: u32,
+    /// The method used to aggregate the prices of each source
+    pub aggregation: Aggregation,
+    /// An independent oracle used to verify the aggregated price. If set, no price is reported
+    /// when the aggregated price diverges too far from the reference price.
+    pub reference: Reference,
+    /// The minimum number of sources that must report a valid price before the
+    /// aggregated price is reported
+    pub min_sources: u32,
+    /// The maximum number of median absolute deviations a source price can be from the median
+    /// source price before it is dropped as an outlier. If this is 0, outliers are not filtered.
+    pub max_mads: u32,
+    /// The number of rounds fetched from the source oracle for the max_dev check. The `Previous`
+    /// mode compares the first 2 rounds returned.
+    pub dev_rounds: u32,
+    /// The price the latest round is compared against for the max_dev check
+    pub dev_mode: DeviationMode,
+    /// The tolerance, in seconds, allowed on top of the source oracle's resolution between the
+    /// rounds compared for the max_dev check. If this is None, the gap between rounds is not checked.
+    pub round_tolerance: Option<u32>,
+    /// The maximum deviation allowed from the last accepted price of the asset, in basis points.
+    /// If this is 0, the price is not checked against the last accepted price.
+    pub max_step_bps: u32,
+    /// The window, in seconds, after the last accepted price during which new prices are checked
+    /// against it
+    pub step_window: u64,
+    /// The maximum change allowed for a stable price over `roc_window`, in basis points. If this
+    /// is 0, the rate of change is not checked.
+    pub max_roc_bps: u32,
+    /// The window, in seconds, of source rounds the rate of change is checked over
+    pub roc_window: u64,
+    /// The hard floor and ceiling of the price of the asset
+    pub bounds: PriceBounds,
+    /// The maximum age of a price of the asset, in seconds. If this is None, the max age of the
+    /// oracle aggregator is used.
+    pub max_age: Option<u64>,
+    /// If the circuit breaker of the asset can be tripped by a deviation or quorum failure
+    pub circuit_breaker: bool,
+    /// The time, in seconds, past the max age the last accepted price of the asset can be
+    /// reported when the sources cannot report a price. If this is 0, no fallback price is reported.
+    pub grace_period: u64,
+}
+
+/// An asset config as stored by the first version of the oracle aggregator, before the storage
+/// layout was versioned
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LegacyAssetConfig {
+    /// The asset used to fetch prices from the source oracle
+    pub asset: Asset,
+    /// The index of the oracle used for this asset
+    pub oracle_index: u32,
+    /// The maximum deviation allowed for a stable price, as a percentage with 0 decimals
+    pub max_dev: u32,
+}
+
+/// The reference oracle used to verify the price of an asset
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum Reference {
+    /// No reference oracle is used
+    None,
+    /// The reference oracle and the allowed divergence from its price
+    Oracle(ReferenceConfig),
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ReferenceConfig {
+    /// The asset used to fetch prices from the reference oracle
+    pub asset: Asset,
+    /// The index of the reference oracle
+    pub oracle_index: u32,
+    /// The maximum divergence allowed between the aggregated price and the reference price,
+    /// as a percentage with 0 decimals (e.g 5 => 5%)
+    pub max_divergence: u32,
+}
+
+/// The hard floor and ceiling of the price of an asset
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum PriceBounds {
+    /// The price is not bounded
+    None,
+    /// The price is bounded, and the policy used when it is out of bounds
+    Bounded(BoundsConfig),
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BoundsConfig {
+    /// The minimum price, in the decimals of the oracle aggregator
+    pub min_price: i128,
+    /// The maximum price, in the decimals of the oracle aggregator
+    pub max_price: i128,
+    /// The policy used when the price is out of bounds
+    pub policy: BoundsPolicy,
+}
+
+/// The policy used when the price of an asset is out of bounds
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BoundsPolicy {
+    /// No price is reported
+    Reject = 0,
+    /// The price is clamped to the nearest bound
+    Clamp = 1,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SourceConfig {
+    /// The asset used to fetch prices from the source oracle
+    pub asset: Asset,
+    /// The index of the oracle used for this source
+    pub oracle_index: u32,
+    /// The weight of the source when using a weighted aggregation
+    pub weight: u32,
+}
+
+/// The price the latest round is compared against when checking the max deviation of a price
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DeviationMode {
+    /// The previous round
+    Previous = 0,
+    /// The median of the prior rounds fetched
+    Median = 1,
+    /// The mean of the prior rounds fetched
+    Mean = 2,
+}
+
+/// The method used to aggregate the prices of an asset's sources
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Aggregation {
+    /// The median of the source prices
+    Median = 0,
+    /// The mean of the source prices, weighted by each source's weight. The weights
+    /// of any sources that cannot report a price are excluded.
+    WeightedMean = 1,
+    /// The price of the first source that can report a price, trying the primary oracle
+    /// first and then each additional source in the order they were added.
+    Fallback = 2,
 }
 
 #[contracttype]
@@ -40,6 +239,24 @@ pub struct OracleConfig {
     pub resolution: u32,
     /// The decimals of the oracle
     pub decimals: u32,
+    /// The number of rounds, in multiples of the resolution, the latest round of the oracle must
+    /// be within of the ledger timestamp. If this is 0, the liveness of the oracle is not checked.
+    pub liveness_rounds: u32,
+}
+
+/// An oracle config as stored by the first version of the oracle aggregator, before the storage
+/// layout was versioned
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LegacyOracleConfig {
+    /// The oracle id
+    pub address: Address,
+    /// The index of the oracle
+    pub index: u32,
+    /// The resolution of the oracle, in seconds
+    pub resolution: u32,
+    /// The decimals of the oracle
+    pub decimals: u32,
 }
 
 #[allow(dead_code)]